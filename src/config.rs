@@ -8,12 +8,26 @@ pub struct Config {
     pub service: ServiceConfig,
     pub pubsub: PubSubConfig,
     pub templates: TemplateConfig,
+    pub render: RenderConfig,
+    pub storage: StorageConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ServiceConfig {
     pub name: String,
     pub log_level: String,
+    pub processing_mode: ProcessingMode,
+}
+
+/// How generated documents are delivered back to the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingMode {
+    /// Documents are returned base64-encoded inside the response message.
+    Inline,
+    /// Documents are recorded in `storage.generated_documents`, uploaded to
+    /// the bucket, and the response carries artifact references.
+    Pipeline,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -22,11 +36,58 @@ pub struct PubSubConfig {
     pub request_subscription: String,
     pub response_topic: String,
     pub max_concurrent_messages: usize,
+    /// Flow control: outstanding bytes the server may deliver before acks.
+    pub max_outstanding_bytes: i64,
+    /// Stream ack deadline; must outlast the slowest render.
+    pub ack_deadline_seconds: i32,
+    /// Topic receiving requests that kept failing with retryable errors.
+    pub dead_letter_topic: String,
+    /// Deliveries attempted before a retryable failure is dead-lettered.
+    pub max_delivery_attempts: usize,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct TemplateConfig {
+    /// Directory holding `manifest.json`, `layouts.json`, the templates,
+    /// `partials/` and `themes/`.
     pub path: String,
+    /// Recompile templates when files under `path` change (template authoring).
+    pub hot_reload: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RenderConfig {
+    /// Upper bound on Pandoc/XeLaTeX subprocesses running at once per pod.
+    pub max_concurrent_renders: usize,
+    /// Word reference document supplying DOCX styles; Pandoc's defaults when unset.
+    pub docx_reference_doc: Option<String>,
+    /// OpenDocument reference file supplying ODT styles; Pandoc's defaults when unset.
+    pub odt_reference_doc: Option<String>,
+    /// Cover image embedded in EPUB output.
+    pub epub_cover_image: Option<String>,
+    /// Seconds each output format's Pandoc/XeLaTeX run may take before it is killed.
+    pub timeout_seconds: RenderTimeouts,
+}
+
+/// Per-format render time limits, in seconds.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RenderTimeouts {
+    pub pdf: u64,
+    pub html: u64,
+    pub docx: u64,
+    pub odt: u64,
+    pub epub: u64,
+    pub asciidoc: u64,
+    pub latex: u64,
+    pub latex_bundle: u64,
+}
+
+/// Database and bucket settings used in `ProcessingMode::Pipeline`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StorageConfig {
+    pub database_url: String,
+    pub database_max_connections: u32,
+    pub bucket: String,
 }
 
 impl Config {
@@ -35,11 +96,29 @@ impl Config {
             // Start with default values
             .set_default("service.name", "document-generation-service")?
             .set_default("service.log_level", "info")?
+            .set_default("service.processing_mode", "inline")?
             .set_default("pubsub.project_id", "mcxtest")?
             .set_default("pubsub.request_subscription", "document-generation-requests-sub")?
             .set_default("pubsub.response_topic", "document-generation-results")?
             .set_default("pubsub.max_concurrent_messages", "10")?
+            .set_default("pubsub.max_outstanding_bytes", "104857600")?
+            .set_default("pubsub.ack_deadline_seconds", "300")?
+            .set_default("pubsub.dead_letter_topic", "document-generation-dead-letter")?
+            .set_default("pubsub.max_delivery_attempts", "5")?
             .set_default("templates.path", "./templates")?
+            .set_default("templates.hot_reload", false)?
+            .set_default("render.max_concurrent_renders", "4")?
+            .set_default("render.timeout_seconds.pdf", "240")?
+            .set_default("render.timeout_seconds.html", "60")?
+            .set_default("render.timeout_seconds.docx", "60")?
+            .set_default("render.timeout_seconds.odt", "60")?
+            .set_default("render.timeout_seconds.epub", "60")?
+            .set_default("render.timeout_seconds.asciidoc", "60")?
+            .set_default("render.timeout_seconds.latex", "60")?
+            .set_default("render.timeout_seconds.latex_bundle", "240")?
+            .set_default("storage.database_url", "")?
+            .set_default("storage.database_max_connections", "5")?
+            .set_default("storage.bucket", "mcxtest-attachments")?
             // Load from config file if it exists
             .add_source(File::with_name("config").required(false))
             // Override with environment variables (e.g., SERVICE__NAME)
@@ -6,8 +6,10 @@ pub type Result<T> = std::result::Result<T, DocumentError>;
 
 #[derive(Error, Debug)]
 pub enum DocumentError {
+    /// Boxed: a compile error carries the template source and is several
+    /// times larger than every other variant.
     #[error("Template error: {0}")]
-    TemplateError(#[from] handlebars::TemplateError),
+    TemplateError(Box<handlebars::TemplateError>),
 
     #[error("Rendering error: {0}")]
     RenderError(#[from] handlebars::RenderError),
@@ -44,9 +46,42 @@ pub enum DocumentError {
 
     #[error("Generation failed: {0}")]
     GenerationFailed(String),
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+impl From<handlebars::TemplateError> for DocumentError {
+    fn from(e: handlebars::TemplateError) -> Self {
+        DocumentError::TemplateError(Box::new(e))
+    }
 }
 
 impl DocumentError {
+    /// Error for request data that failed validation: `MissingField` when
+    /// every violation is an absent required field, otherwise `InvalidData`.
+    pub fn from_violations(violations: &[Violation]) -> Self {
+        if violations.iter().all(Violation::is_missing) {
+            let fields: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+            return DocumentError::MissingField(fields.join(", "));
+        }
+        let problems: Vec<String> = violations.iter().map(ToString::to_string).collect();
+        DocumentError::InvalidData(problems.join("; "))
+    }
+
+    /// Whether the failure may succeed on redelivery (a crashed Pandoc run,
+    /// a transient IO, Pub/Sub, database or bucket fault) rather than being a
+    /// problem with the request itself.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            DocumentError::PandocError(_)
+                | DocumentError::IoError(_)
+                | DocumentError::PubSubError(_)
+                | DocumentError::StorageError(_)
+        )
+    }
+
     pub fn to_error_response(&self) -> ErrorResponse {
         ErrorResponse {
             error: self.to_string(),
@@ -64,6 +99,7 @@ impl DocumentError {
                 DocumentError::Base64Error(_) => "base64_error",
                 DocumentError::InvalidData(_) => "invalid_data",
                 DocumentError::GenerationFailed(_) => "generation_failed",
+                DocumentError::StorageError(_) => "storage_error",
             }
             .to_string(),
         }
@@ -75,3 +111,65 @@ pub struct ErrorResponse {
     pub error: String,
     pub error_type: String,
 }
+
+/// One problem found when validating request data against its schema.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Violation {
+    /// JSON pointer into the request `data`, e.g. `/requirements/0/id`.
+    pub path: String,
+    /// JSON Schema keyword that failed (`required`, `type`, ...).
+    pub keyword: String,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn is_missing(&self) -> bool {
+        self.keyword == "required"
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        write!(f, "{}: {}", path, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_transient_faults_are_retryable() {
+        let errors = [
+            handlebars::Template::compile("{{#if}}").unwrap_err().into(),
+            DocumentError::RenderError(handlebars::RenderError::new("missing helper")),
+            DocumentError::IoError(std::io::Error::from(std::io::ErrorKind::BrokenPipe)),
+            DocumentError::PandocError("killed".to_string()),
+            DocumentError::SerializationError(serde_json::from_str::<()>("{").unwrap_err()),
+            DocumentError::InvalidFormat("Word97".to_string()),
+            DocumentError::InvalidSpecificationType("nasa_srs".to_string()),
+            DocumentError::MissingField("/requirements".to_string()),
+            DocumentError::TemplateNotFound("srs".to_string()),
+            DocumentError::PubSubError("unavailable".to_string()),
+            DocumentError::Base64Error(base64::DecodeError::InvalidLength),
+            DocumentError::InvalidData("/id: not a string".to_string()),
+            DocumentError::GenerationFailed("no output".to_string()),
+            DocumentError::StorageError("pool timed out".to_string()),
+        ];
+
+        let retryable: Vec<String> = errors
+            .iter()
+            .filter(|e| e.is_retryable())
+            .map(|e| e.to_error_response().error_type)
+            .collect();
+        assert_eq!(
+            retryable,
+            ["io_error", "pandoc_error", "pubsub_error", "storage_error"]
+        );
+    }
+}
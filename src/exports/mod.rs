@@ -0,0 +1,7 @@
+// document-generation-service/src/exports/mod.rs
+
+//! Machine-readable exports of the data behind a document, emitted alongside
+//! the prose renderings.
+
+pub mod reqif;
+pub mod requirements;
@@ -0,0 +1,446 @@
+// document-generation-service/src/exports/reqif.rs
+
+use crate::error::Result;
+use crate::exports::requirements::{Requirement, RequirementSet};
+use crate::models::DocumentMetadata;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+const REQIF_NAMESPACE: &str = "http://www.omg.org/spec/ReqIF/20110401/reqif.xsd";
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+const TOOL_ID: &str = "document-generation-service";
+
+const STRING_TYPE: &str = "_datatype-string";
+const XHTML_TYPE: &str = "_datatype-xhtml";
+const REQUIREMENT_TYPE: &str = "_type-requirement";
+const RELATION_TYPE: &str = "_type-derived-from";
+const SPECIFICATION_TYPE: &str = "_type-specification";
+
+/// Attribute identifier, ReqIF long name and the requirement field it holds.
+type Attribute = (
+    &'static str,
+    &'static str,
+    fn(&Requirement) -> Option<String>,
+);
+
+/// String attributes of a requirement.
+const STRING_ATTRIBUTES: &[Attribute] = &[
+    ("_attribute-id", "ReqIF.ForeignID", |r| Some(r.id.clone())),
+    ("_attribute-name", "ReqIF.Name", |r| r.title.clone()),
+    ("_attribute-type", "Type", |r| r.kind.clone()),
+];
+
+/// Enumerated attributes. Each gets its own datatype whose values are those
+/// found in the set.
+const ENUM_ATTRIBUTES: &[Attribute] = &[
+    ("_attribute-priority", "Priority", |r| r.priority.clone()),
+    ("_attribute-criticality", "Criticality", |r| {
+        r.criticality.clone()
+    }),
+    (
+        "_attribute-verification-method",
+        "Verification Method",
+        |r| r.verification_method.clone(),
+    ),
+];
+
+const TEXT_ATTRIBUTE: &str = "_attribute-text";
+const EXTERNAL_LINKS_ATTRIBUTE: &str = "_attribute-external-links";
+
+/// String attribute definitions, the external links last.
+fn string_definitions() -> impl Iterator<Item = (&'static str, &'static str)> {
+    STRING_ATTRIBUTES
+        .iter()
+        .map(|(attribute, name, _)| (*attribute, *name))
+        .chain([(EXTERNAL_LINKS_ATTRIBUTE, "External Links")])
+}
+
+/// Serialize the set as a ReqIF 1.2 exchange document: one SPEC-OBJECT per
+/// requirement in a single SPECIFICATION, and a "Derived From" SPEC-RELATION
+/// from each requirement to every parent in the set. Links to requirements
+/// outside the set are kept in the "External Links" attribute.
+pub fn to_reqif(set: &RequirementSet, metadata: &DocumentMetadata) -> Result<Vec<u8>> {
+    let now = metadata.generated_date.to_rfc3339();
+    let objects = object_identifiers(&set.requirements);
+    let enums: Vec<Vec<String>> = ENUM_ATTRIBUTES
+        .iter()
+        .map(|(_, _, value)| {
+            let mut values: Vec<String> = Vec::new();
+            for v in set.requirements.iter().filter_map(value) {
+                if !values.contains(&v) {
+                    values.push(v);
+                }
+            }
+            values
+        })
+        .collect();
+
+    let mut xml = String::new();
+    let _ = write!(
+        xml,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<REQ-IF xmlns="{}" xmlns:xhtml="{}">
+  <THE-HEADER>
+    <REQ-IF-HEADER IDENTIFIER="_header">
+      <CREATION-TIME>{}</CREATION-TIME>
+      <REPOSITORY-ID>{}</REPOSITORY-ID>
+      <REQ-IF-TOOL-ID>{}</REQ-IF-TOOL-ID>
+      <REQ-IF-VERSION>1.0</REQ-IF-VERSION>
+      <SOURCE-TOOL-ID>{}</SOURCE-TOOL-ID>
+      <TITLE>{}</TITLE>
+    </REQ-IF-HEADER>
+  </THE-HEADER>
+  <CORE-CONTENT>
+    <REQ-IF-CONTENT>
+      <DATATYPES>
+        <DATATYPE-DEFINITION-STRING IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="String" MAX-LENGTH="32000"/>
+        <DATATYPE-DEFINITION-XHTML IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="XHTML"/>
+"#,
+        REQIF_NAMESPACE,
+        XHTML_NAMESPACE,
+        now,
+        escape(&set.project_name),
+        TOOL_ID,
+        TOOL_ID,
+        escape(&set.title),
+        STRING_TYPE,
+        now,
+        XHTML_TYPE,
+        now,
+    );
+
+    for ((attribute, name, _), values) in ENUM_ATTRIBUTES.iter().zip(&enums) {
+        let _ = writeln!(
+            xml,
+            r#"        <DATATYPE-DEFINITION-ENUMERATION IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="{}">
+          <SPECIFIED-VALUES>"#,
+            enum_type(attribute),
+            now,
+            name
+        );
+        for (key, value) in values.iter().enumerate() {
+            let _ = writeln!(
+                xml,
+                r#"            <ENUM-VALUE IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="{}">
+              <PROPERTIES><EMBEDDED-VALUE KEY="{}" OTHER-CONTENT=""/></PROPERTIES>
+            </ENUM-VALUE>"#,
+                enum_value(attribute, key),
+                now,
+                escape(value),
+                key
+            );
+        }
+        xml.push_str("          </SPECIFIED-VALUES>\n        </DATATYPE-DEFINITION-ENUMERATION>\n");
+    }
+
+    let _ = write!(
+        xml,
+        r#"      </DATATYPES>
+      <SPEC-TYPES>
+        <SPEC-OBJECT-TYPE IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="Requirement">
+          <SPEC-ATTRIBUTES>
+"#,
+        REQUIREMENT_TYPE, now
+    );
+    for (attribute, name) in string_definitions() {
+        let _ = writeln!(
+            xml,
+            r#"            <ATTRIBUTE-DEFINITION-STRING IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="{}">
+              <TYPE><DATATYPE-DEFINITION-STRING-REF>{}</DATATYPE-DEFINITION-STRING-REF></TYPE>
+            </ATTRIBUTE-DEFINITION-STRING>"#,
+            attribute, now, name, STRING_TYPE
+        );
+    }
+    let _ = writeln!(
+        xml,
+        r#"            <ATTRIBUTE-DEFINITION-XHTML IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="ReqIF.Text">
+              <TYPE><DATATYPE-DEFINITION-XHTML-REF>{}</DATATYPE-DEFINITION-XHTML-REF></TYPE>
+            </ATTRIBUTE-DEFINITION-XHTML>"#,
+        TEXT_ATTRIBUTE, now, XHTML_TYPE
+    );
+    for (attribute, name, _) in ENUM_ATTRIBUTES {
+        let _ = writeln!(
+            xml,
+            r#"            <ATTRIBUTE-DEFINITION-ENUMERATION IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="{}" MULTI-VALUED="false">
+              <TYPE><DATATYPE-DEFINITION-ENUMERATION-REF>{}</DATATYPE-DEFINITION-ENUMERATION-REF></TYPE>
+            </ATTRIBUTE-DEFINITION-ENUMERATION>"#,
+            attribute,
+            now,
+            name,
+            enum_type(attribute)
+        );
+    }
+    let _ = write!(
+        xml,
+        r#"          </SPEC-ATTRIBUTES>
+        </SPEC-OBJECT-TYPE>
+        <SPEC-RELATION-TYPE IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="Derived From"/>
+        <SPECIFICATION-TYPE IDENTIFIER="{}" LAST-CHANGE="{}" LONG-NAME="Requirements Specification"/>
+      </SPEC-TYPES>
+      <SPEC-OBJECTS>
+"#,
+        RELATION_TYPE, now, SPECIFICATION_TYPE, now
+    );
+
+    for requirement in &set.requirements {
+        let _ = writeln!(
+            xml,
+            r#"        <SPEC-OBJECT IDENTIFIER="{}" LAST-CHANGE="{}">
+          <VALUES>"#,
+            objects[&requirement.id], now
+        );
+        let strings = STRING_ATTRIBUTES
+            .iter()
+            .map(|(attribute, _, value)| (*attribute, value(requirement)))
+            .chain([(
+                EXTERNAL_LINKS_ATTRIBUTE,
+                external_links(requirement, &objects),
+            )]);
+        for (attribute, value) in strings {
+            if let Some(value) = value {
+                let _ = writeln!(
+                    xml,
+                    r#"            <ATTRIBUTE-VALUE-STRING THE-VALUE="{}">
+              <DEFINITION><ATTRIBUTE-DEFINITION-STRING-REF>{}</ATTRIBUTE-DEFINITION-STRING-REF></DEFINITION>
+            </ATTRIBUTE-VALUE-STRING>"#,
+                    escape(&value),
+                    attribute
+                );
+            }
+        }
+        if let Some(statement) = &requirement.statement {
+            let _ = writeln!(
+                xml,
+                r#"            <ATTRIBUTE-VALUE-XHTML>
+              <DEFINITION><ATTRIBUTE-DEFINITION-XHTML-REF>{}</ATTRIBUTE-DEFINITION-XHTML-REF></DEFINITION>
+              <THE-VALUE><xhtml:div>{}</xhtml:div></THE-VALUE>
+            </ATTRIBUTE-VALUE-XHTML>"#,
+                TEXT_ATTRIBUTE,
+                escape(statement)
+            );
+        }
+        for ((attribute, _, value), values) in ENUM_ATTRIBUTES.iter().zip(&enums) {
+            let Some(key) = value(requirement).and_then(|v| values.iter().position(|x| *x == v))
+            else {
+                continue;
+            };
+            let _ = writeln!(
+                xml,
+                r#"            <ATTRIBUTE-VALUE-ENUMERATION>
+              <DEFINITION><ATTRIBUTE-DEFINITION-ENUMERATION-REF>{}</ATTRIBUTE-DEFINITION-ENUMERATION-REF></DEFINITION>
+              <VALUES><ENUM-VALUE-REF>{}</ENUM-VALUE-REF></VALUES>
+            </ATTRIBUTE-VALUE-ENUMERATION>"#,
+                attribute,
+                enum_value(attribute, key)
+            );
+        }
+        let _ = writeln!(
+            xml,
+            r#"          </VALUES>
+          <TYPE><SPEC-OBJECT-TYPE-REF>{}</SPEC-OBJECT-TYPE-REF></TYPE>
+        </SPEC-OBJECT>"#,
+            REQUIREMENT_TYPE
+        );
+    }
+
+    xml.push_str("      </SPEC-OBJECTS>\n      <SPEC-RELATIONS>\n");
+    for (index, (child, parent)) in relations(&set.requirements, &objects).iter().enumerate() {
+        let _ = writeln!(
+            xml,
+            r#"        <SPEC-RELATION IDENTIFIER="_relation-{}" LAST-CHANGE="{}">
+          <SOURCE><SPEC-OBJECT-REF>{}</SPEC-OBJECT-REF></SOURCE>
+          <TARGET><SPEC-OBJECT-REF>{}</SPEC-OBJECT-REF></TARGET>
+          <TYPE><SPEC-RELATION-TYPE-REF>{}</SPEC-RELATION-TYPE-REF></TYPE>
+        </SPEC-RELATION>"#,
+            index + 1,
+            now,
+            child,
+            parent,
+            RELATION_TYPE
+        );
+    }
+
+    let _ = writeln!(
+        xml,
+        r#"      </SPEC-RELATIONS>
+      <SPECIFICATIONS>
+        <SPECIFICATION IDENTIFIER="_specification" LAST-CHANGE="{}" LONG-NAME="{}">
+          <TYPE><SPECIFICATION-TYPE-REF>{}</SPECIFICATION-TYPE-REF></TYPE>
+          <CHILDREN>"#,
+        now,
+        escape(&set.title),
+        SPECIFICATION_TYPE
+    );
+    for requirement in &set.requirements {
+        let object = &objects[&requirement.id];
+        let _ = writeln!(
+            xml,
+            r#"            <SPEC-HIERARCHY IDENTIFIER="{}-hierarchy" LAST-CHANGE="{}">
+              <OBJECT><SPEC-OBJECT-REF>{}</SPEC-OBJECT-REF></OBJECT>
+            </SPEC-HIERARCHY>"#,
+            object, now, object
+        );
+    }
+    xml.push_str(
+        r#"          </CHILDREN>
+        </SPECIFICATION>
+      </SPECIFICATIONS>
+    </REQ-IF-CONTENT>
+  </CORE-CONTENT>
+</REQ-IF>
+"#,
+    );
+
+    Ok(xml.into_bytes())
+}
+
+/// SPEC-OBJECT identifiers keyed by requirement ID. They are derived from the
+/// requirement IDs so re-imports update the same objects.
+fn object_identifiers(requirements: &[Requirement]) -> HashMap<String, String> {
+    let mut used = HashSet::new();
+    let mut identifiers = HashMap::new();
+    for requirement in requirements {
+        let base: String = requirement
+            .id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let mut identifier = format!("_requirement-{}", base);
+        let mut suffix = 1;
+        while !used.insert(identifier.clone()) {
+            suffix += 1;
+            identifier = format!("_requirement-{}-{}", base, suffix);
+        }
+        identifiers.insert(requirement.id.clone(), identifier);
+    }
+    identifiers
+}
+
+/// (child, parent) object pairs for every link within the set.
+fn relations(
+    requirements: &[Requirement],
+    objects: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    for requirement in requirements {
+        let links = requirement
+            .parents
+            .iter()
+            .map(|parent| (&requirement.id, parent))
+            .chain(
+                requirement
+                    .children
+                    .iter()
+                    .map(|child| (child, &requirement.id)),
+            );
+        for (child, parent) in links {
+            if let (Some(child), Some(parent)) = (objects.get(child), objects.get(parent)) {
+                let pair = (child.clone(), parent.clone());
+                if !pairs.contains(&pair) {
+                    pairs.push(pair);
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// Parents and children that are not part of this set, e.g. stakeholder
+/// requirements traced from an SRS.
+fn external_links(requirement: &Requirement, objects: &HashMap<String, String>) -> Option<String> {
+    let links: Vec<String> = requirement
+        .parents
+        .iter()
+        .filter(|id| !objects.contains_key(*id))
+        .map(|id| format!("parent {}", id))
+        .chain(
+            requirement
+                .children
+                .iter()
+                .filter(|id| !objects.contains_key(*id))
+                .map(|id| format!("child {}", id)),
+        )
+        .collect();
+    (!links.is_empty()).then(|| links.join("; "))
+}
+
+fn enum_type(attribute: &str) -> String {
+    format!("_datatype{}", &attribute["_attribute".len()..])
+}
+
+fn enum_value(attribute: &str, key: usize) -> String {
+    format!("{}-{}", enum_type(attribute), key)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::requirements::parse_reqif;
+    use chrono::Utc;
+
+    fn requirement(id: &str, parents: &[&str], children: &[&str]) -> Requirement {
+        Requirement {
+            id: id.to_string(),
+            title: Some(format!("{} <title> & more", id)),
+            kind: Some("Functional".to_string()),
+            priority: Some("High".to_string()),
+            criticality: Some("Mission \"critical\"".to_string()),
+            statement: Some(format!("The system shall satisfy {}.", id)),
+            verification_method: Some("Test".to_string()),
+            parents: parents.iter().map(|p| p.to_string()).collect(),
+            children: children.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn reqif_round_trips_through_the_importer() {
+        let mut untitled = requirement("SYS-003", &["SYS-001", "SYS-002"], &["TST-9"]);
+        untitled.title = None;
+        untitled.priority = Some("Low".to_string());
+        untitled.verification_method = None;
+        let set = RequirementSet {
+            specification_type: "iso29148_system_requirements".to_string(),
+            title: "System Requirements".to_string(),
+            project_name: "Project".to_string(),
+            version: "1.0".to_string(),
+            requirements: vec![
+                requirement("SYS-001", &["STK-1"], &[]),
+                requirement("SYS-002", &["SYS-001"], &[]),
+                untitled,
+            ],
+        };
+        let metadata = DocumentMetadata {
+            title: set.title.clone(),
+            project_name: set.project_name.clone(),
+            version: set.version.clone(),
+            author: "Author".to_string(),
+            organization: "Organization".to_string(),
+            classification: None,
+            distribution_statement: None,
+            generated_date: Utc::now(),
+            theme: None,
+            layout: None,
+        };
+
+        let xml = to_reqif(&set, &metadata).unwrap();
+        let parsed = parse_reqif(std::str::from_utf8(&xml).unwrap()).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&set.requirements).unwrap()
+        );
+    }
+}
@@ -0,0 +1,408 @@
+// document-generation-service/src/exports/requirements.rs
+
+use crate::error::{DocumentError, Result};
+use crate::generators::normalize;
+use crate::models::DocumentMetadata;
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Column headings shared by the CSV and XLSX exports.
+const COLUMNS: &[&str] = &[
+    "ID",
+    "Title",
+    "Type",
+    "Priority",
+    "Criticality",
+    "Statement",
+    "Verification Method",
+    "Parents",
+    "Children",
+];
+
+/// Separator for multiple links in one CSV/XLSX cell.
+const LINK_SEPARATOR: &str = "; ";
+
+/// Leading characters that make a spreadsheet read a CSV cell as a formula.
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+/// One requirement as exported: the fields a PM tracks plus its trace links
+/// to higher-level (`parents`) and derived (`children`) requirements.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Requirement {
+    pub id: String,
+    pub title: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub priority: Option<String>,
+    pub criticality: Option<String>,
+    pub statement: Option<String>,
+    pub verification_method: Option<String>,
+    pub parents: Vec<String>,
+    pub children: Vec<String>,
+}
+
+/// The requirement set of one document, read from the same normalized data
+/// the templates render.
+#[derive(Debug, Clone, Serialize)]
+pub struct RequirementSet {
+    pub specification_type: String,
+    pub title: String,
+    pub project_name: String,
+    pub version: String,
+    pub requirements: Vec<Requirement>,
+}
+
+impl RequirementSet {
+    pub fn from_data(spec_type: &str, data: &Value, metadata: &DocumentMetadata) -> Result<Self> {
+        let data = normalize(spec_type, data);
+        let mut requirements = match spec_type {
+            "iso29148_system_requirements" => system_requirements(&data),
+            "iso29148_software_requirements" => software_requirements(&data),
+            "iso29148_stakeholder_requirements" => stakeholder_requirements(&data),
+            _ => {
+                return Err(DocumentError::InvalidFormat(format!(
+                    "requirements export is not available for {}",
+                    spec_type
+                )))
+            }
+        };
+        link_children(&mut requirements);
+
+        Ok(Self {
+            specification_type: spec_type.to_string(),
+            title: metadata.title.clone(),
+            project_name: metadata.project_name.clone(),
+            version: metadata.version.clone(),
+            requirements,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(self)?)
+    }
+
+    pub fn to_csv(&self) -> Result<Vec<u8>> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(COLUMNS).map_err(csv_error)?;
+        for requirement in &self.requirements {
+            writer
+                .write_record(row(requirement).into_iter().map(neutralize_formula))
+                .map_err(csv_error)?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| DocumentError::GenerationFailed(format!("requirements CSV: {}", e)))
+    }
+
+    pub fn to_xlsx(&self) -> Result<Vec<u8>> {
+        let mut workbook = Workbook::new();
+        let sheet = workbook.add_worksheet();
+        sheet.set_name("Requirements").map_err(xlsx_error)?;
+
+        let header = Format::new().set_bold();
+        for (col, heading) in COLUMNS.iter().enumerate() {
+            sheet
+                .write_string_with_format(0, col as u16, *heading, &header)
+                .map_err(xlsx_error)?;
+        }
+        for (index, requirement) in self.requirements.iter().enumerate() {
+            for (col, cell) in row(requirement).iter().enumerate() {
+                sheet
+                    .write_string(index as u32 + 1, col as u16, cell)
+                    .map_err(xlsx_error)?;
+            }
+        }
+
+        sheet.set_freeze_panes(1, 0).map_err(xlsx_error)?;
+        sheet
+            .autofilter(
+                0,
+                0,
+                self.requirements.len() as u32,
+                COLUMNS.len() as u16 - 1,
+            )
+            .map_err(xlsx_error)?;
+        sheet.autofit();
+
+        workbook.save_to_buffer().map_err(xlsx_error)
+    }
+}
+
+/// `requirements` with their `parent_requirements`/`child_requirements`,
+/// plus the links in the `traceability` matrix.
+fn system_requirements(data: &Value) -> Vec<Requirement> {
+    let mut requirements: Vec<Requirement> = items(data, "requirements")
+        .map(|item| Requirement {
+            title: text(item, "title"),
+            kind: text(item, "type"),
+            priority: text(item, "priority"),
+            criticality: text(item, "criticality"),
+            statement: text(item, "description"),
+            verification_method: text(item, "verification_method"),
+            parents: links(item, "parent_requirements"),
+            children: links(item, "child_requirements"),
+            ..requirement(item)
+        })
+        .collect();
+
+    for trace in items(data, "traceability") {
+        let Some(id) = text(trace, "sys_req_id") else {
+            continue;
+        };
+        if let Some(requirement) = requirements.iter_mut().find(|r| r.id == id) {
+            requirement.parents.extend(links(trace, "stak_req_id"));
+            requirement.children.extend(links(trace, "sw_req_ids"));
+        }
+    }
+
+    dedup(requirements)
+}
+
+/// `requirements`, the categorized functional items and the non-functional
+/// lists, with links from `parent_system_req`, `stakeholder_req` and the
+/// `traceability` matrix.
+fn software_requirements(data: &Value) -> Vec<Requirement> {
+    let mut requirements: Vec<Requirement> = items(data, "requirements")
+        .map(|item| Requirement {
+            title: text(item, "title"),
+            kind: text(item, "type"),
+            priority: text(item, "priority"),
+            criticality: text(item, "criticality"),
+            statement: text(item, "statement"),
+            verification_method: text(item, "verification_method"),
+            parents: [
+                links(item, "parent_system_req"),
+                links(item, "stakeholder_req"),
+            ]
+            .concat(),
+            ..requirement(item)
+        })
+        .collect();
+
+    for category in items(data, "functional_requirements") {
+        requirements.extend(items(category, "items").map(|item| Requirement {
+            title: text(item, "title"),
+            kind: text(category, "category").or_else(|| Some("Functional".to_string())),
+            statement: text(item, "description"),
+            ..requirement(item)
+        }));
+    }
+
+    for (key, kind) in [
+        ("performance_requirements", "Performance"),
+        ("security_requirements", "Security"),
+        ("reliability_requirements", "Reliability"),
+    ] {
+        requirements.extend(items(data, key).map(|item| Requirement {
+            kind: Some(kind.to_string()),
+            statement: text(item, "description"),
+            ..requirement(item)
+        }));
+    }
+
+    for trace in items(data, "traceability") {
+        let Some(id) = text(trace, "sw_req") else {
+            continue;
+        };
+        let parents = [links(trace, "sys_req"), links(trace, "stak_req")].concat();
+        if let Some(requirement) = requirements.iter_mut().find(|r| r.id == id) {
+            requirement.parents.extend(parents);
+        }
+    }
+
+    dedup(requirements)
+}
+
+/// `requirements` (or the documented `stakeholder_requirements`), traced to
+/// their business objectives.
+fn stakeholder_requirements(data: &Value) -> Vec<Requirement> {
+    let requirements = items(data, "requirements")
+        .map(|item| Requirement {
+            title: text(item, "title"),
+            kind: text(item, "type"),
+            priority: text(item, "priority"),
+            criticality: text(item, "criticality"),
+            statement: text(item, "description"),
+            verification_method: item.get("verification").and_then(|v| text(v, "method")),
+            parents: links(item, "business_objective"),
+            ..requirement(item)
+        })
+        .collect();
+
+    dedup(requirements)
+}
+
+/// A requirement carrying only the item's `id`.
+fn requirement(item: &Value) -> Requirement {
+    Requirement {
+        id: text(item, "id").unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Drop entries without an ID, merge repeated IDs and tidy their links.
+fn dedup(requirements: Vec<Requirement>) -> Vec<Requirement> {
+    let mut merged: Vec<Requirement> = Vec::with_capacity(requirements.len());
+    for requirement in requirements.into_iter().filter(|r| !r.id.is_empty()) {
+        match merged.iter_mut().find(|r| r.id == requirement.id) {
+            Some(existing) => {
+                existing.parents.extend(requirement.parents);
+                existing.children.extend(requirement.children);
+            }
+            None => merged.push(requirement),
+        }
+    }
+    for requirement in &mut merged {
+        let id = requirement.id.clone();
+        requirement.parents.retain(|parent| *parent != id);
+        requirement.children.retain(|child| *child != id);
+        unique(&mut requirement.parents);
+        unique(&mut requirement.children);
+    }
+    merged
+}
+
+/// Add to each requirement's `children` the requirements that declare it as
+/// a parent.
+fn link_children(requirements: &mut [Requirement]) {
+    let links: Vec<(String, String)> = requirements
+        .iter()
+        .flat_map(|child| {
+            child
+                .parents
+                .iter()
+                .map(move |parent| (parent.clone(), child.id.clone()))
+        })
+        .collect();
+    for (parent, child) in links {
+        if let Some(requirement) = requirements.iter_mut().find(|r| r.id == parent) {
+            requirement.children.push(child);
+        }
+    }
+    for requirement in requirements {
+        unique(&mut requirement.children);
+    }
+}
+
+fn unique(values: &mut Vec<String>) {
+    let mut seen = std::collections::HashSet::new();
+    values.retain(|value| seen.insert(value.clone()));
+}
+
+fn row(requirement: &Requirement) -> Vec<String> {
+    let cell = |value: &Option<String>| value.clone().unwrap_or_default();
+    vec![
+        requirement.id.clone(),
+        cell(&requirement.title),
+        cell(&requirement.kind),
+        cell(&requirement.priority),
+        cell(&requirement.criticality),
+        cell(&requirement.statement),
+        cell(&requirement.verification_method),
+        requirement.parents.join(LINK_SEPARATOR),
+        requirement.children.join(LINK_SEPARATOR),
+    ]
+}
+
+/// The object elements of the array at `key`.
+fn items<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = &'a Value> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|item| item.is_object())
+}
+
+/// A non-empty string or number field as text.
+fn text(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Link IDs from a field holding one ID, a comma-separated list of them or
+/// an array.
+fn links(value: &Value, key: &str) -> Vec<String> {
+    match value.get(key) {
+        Some(Value::Array(ids)) => ids
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(|id| !id.trim().is_empty())
+            .map(str::to_string)
+            .collect(),
+        Some(_) => text(value, key)
+            .iter()
+            .flat_map(|ids| ids.split(','))
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Quote a cell that a spreadsheet would evaluate, so `=HYPERLINK(...)` in a
+/// statement is shown as text. The requirements import strips the quote.
+fn neutralize_formula(cell: String) -> String {
+    if needs_formula_quote(&cell) {
+        format!("'{}", cell)
+    } else {
+        cell
+    }
+}
+
+/// Whether the CSV export quotes `cell`. A formula already behind
+/// apostrophes gets one more, so stripping a single `'` on import restores
+/// the cell exactly.
+pub(crate) fn needs_formula_quote(cell: &str) -> bool {
+    cell.trim_start_matches('\'').starts_with(FORMULA_PREFIXES)
+}
+
+fn csv_error(e: csv::Error) -> DocumentError {
+    DocumentError::GenerationFailed(format!("requirements CSV: {}", e))
+}
+
+fn xlsx_error(e: XlsxError) -> DocumentError {
+    DocumentError::GenerationFailed(format!("requirements XLSX: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::requirements::parse_csv;
+
+    #[test]
+    fn csv_quotes_formula_cells() {
+        let set = RequirementSet {
+            specification_type: "iso29148_system_requirements".to_string(),
+            title: "System Requirements".to_string(),
+            project_name: "Project".to_string(),
+            version: "1.0".to_string(),
+            requirements: vec![Requirement {
+                id: "SYS-001".to_string(),
+                title: Some("=HYPERLINK(\"http://example.com\")".to_string()),
+                kind: Some("+Functional".to_string()),
+                priority: Some("-1".to_string()),
+                criticality: Some("@SUM(A1:A2)".to_string()),
+                statement: Some("The system shall accept a = b.".to_string()),
+                verification_method: Some("'=Test".to_string()),
+                ..Default::default()
+            }],
+        };
+
+        let csv = String::from_utf8(set.to_csv().unwrap()).unwrap();
+
+        assert!(csv.contains("\"'=HYPERLINK(\"\"http://example.com\"\")\""));
+        assert!(csv.contains(",'+Functional,'-1,'@SUM(A1:A2),The system shall"));
+        assert!(csv.contains(",''=Test,"));
+        let imported = parse_csv(csv.as_bytes()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&imported).unwrap(),
+            serde_json::to_value(&set.requirements).unwrap()
+        );
+    }
+}
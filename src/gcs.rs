@@ -1,17 +1,13 @@
 use anyhow::{Context, Result};
 use google_cloud_storage::client::{Client as GcsClient, ClientConfig};
-use google_cloud_storage::http::objects::delete::DeleteObjectRequest;
+use google_cloud_storage::http::objects::download::Range;
+use google_cloud_storage::http::objects::get::GetObjectRequest;
 use google_cloud_storage::http::objects::upload::{Media, UploadObjectRequest, UploadType};
-use google_cloud_storage::sign::SignedURLMethod;
-use google_cloud_storage::sign::SignedURLOptions;
+use google_cloud_storage::http::Error as HttpError;
 use sha2::{Digest, Sha256};
-use std::time::Duration;
 use tracing::{info, instrument};
 use uuid::Uuid;
 
-const BUCKET: &str = "mcxtest-attachments";
-const SIGNED_URL_EXPIRY: Duration = Duration::from_secs(15 * 60); // 15 minutes
-
 /// Rendered artifact ready for upload.
 #[derive(Debug, Clone)]
 pub struct RenderedFile {
@@ -44,7 +40,7 @@ pub struct DocumentStorage {
 
 impl DocumentStorage {
     /// Initialise from the mounted GCS service account key.
-    pub async fn new() -> Result<Self> {
+    pub async fn new(bucket: &str) -> Result<Self> {
         let config = ClientConfig::default()
             .with_auth()
             .await
@@ -54,7 +50,7 @@ impl DocumentStorage {
 
         Ok(Self {
             client,
-            bucket: BUCKET.to_string(),
+            bucket: bucket.to_string(),
         })
     }
 
@@ -143,62 +139,34 @@ impl DocumentStorage {
         Ok(results)
     }
 
-    /// Generate a signed URL for downloading an artifact.
-    #[instrument(skip(self), fields(bucket = %self.bucket))]
-    pub async fn generate_signed_url(
-        &self,
-        gcs_path: &str,
-        file_name: &str,
-    ) -> Result<String> {
-        let disposition = format!("attachment; filename=\"{}\"", file_name);
+    /// Name of the configured bucket.
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
 
-        let url = self
+    /// Download an object from the configured bucket. A missing object is
+    /// `None` rather than an error, so callers can tell it from a bucket fault.
+    #[instrument(skip(self), fields(bucket = %self.bucket))]
+    pub async fn download(&self, object: &str) -> Result<Option<Vec<u8>>> {
+        let result = self
             .client
-            .signed_url(
-                &self.bucket,
-                gcs_path,
-                None,
-                None,
-                SignedURLOptions {
-                    method: SignedURLMethod::GET,
-                    expires: SIGNED_URL_EXPIRY,
-                    query_parameters: Some(
-                        vec![("response-content-disposition".to_string(), disposition)]
-                            .into_iter()
-                            .collect(),
-                    ),
+            .download_object(
+                &GetObjectRequest {
+                    bucket: self.bucket.clone(),
+                    object: object.to_string(),
                     ..Default::default()
                 },
+                &Range::default(),
             )
-            .await
-            .with_context(|| format!("Failed to generate signed URL for {}", gcs_path))?;
-
-        Ok(url)
-    }
-
-    /// Delete a single object from GCS.
-    #[instrument(skip(self), fields(bucket = %self.bucket))]
-    pub async fn delete_object(&self, gcs_path: &str) -> Result<()> {
-        self.client
-            .delete_object(&DeleteObjectRequest {
-                bucket: self.bucket.clone(),
-                object: gcs_path.to_string(),
-                ..Default::default()
-            })
-            .await
-            .with_context(|| format!("Failed to delete GCS object {}", gcs_path))?;
-
-        info!(gcs_path = %gcs_path, "Deleted GCS object");
-        Ok(())
-    }
-
-    /// Delete all GCS objects for a document given their paths.
-    pub async fn delete_objects(&self, gcs_paths: &[String]) -> Result<()> {
-        for path in gcs_paths {
-            if let Err(e) = self.delete_object(path).await {
-                tracing::warn!(error = %e, path = %path, "Failed to delete GCS object, continuing");
+            .await;
+        let data = match result {
+            Err(HttpError::Response(response)) if response.code == 404 => return Ok(None),
+            result => {
+                result.with_context(|| format!("Failed to download GCS object {}", object))?
             }
-        }
-        Ok(())
+        };
+
+        info!(object = %object, file_size = data.len(), "Downloaded GCS object");
+        Ok(Some(data))
     }
 }
@@ -0,0 +1,590 @@
+// document-generation-service/src/generators/compliance_report.rs
+
+use crate::error::{DocumentError, Result};
+use crate::generators::{Generator, TemplateRegistry};
+use crate::ingest::oscal;
+use crate::models::DocumentMetadata;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use tracing::info;
+
+/// Compliance audit data, given directly or read from NIST OSCAL
+/// `assessment-results` and `plan-of-action-and-milestones` documents.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComplianceAuditData {
+    pub executive_summary: String,
+    pub audit: AuditInfo,
+    pub controls: Vec<Control>,
+    pub risks: Vec<Risk>,
+    pub observations: Vec<Observation>,
+    /// POA&M entries not given on a single control.
+    pub poam: Vec<PoamEntry>,
+}
+
+impl ComplianceAuditData {
+    /// Add the controls, risks, observations and POA&M entries of `other`,
+    /// and take its audit details where these are not set. A control of
+    /// `other` with the same ID as one already listed only fills in the
+    /// fields that control leaves empty, so it is not counted twice.
+    fn merge(&mut self, other: ComplianceAuditData) {
+        if self.executive_summary.trim().is_empty() {
+            self.executive_summary = other.executive_summary;
+        }
+        let (audit, from) = (&mut self.audit, other.audit);
+        audit.framework = audit.framework.take().or(from.framework);
+        for (field, value) in [
+            (&mut audit.scope, from.scope),
+            (&mut audit.methodology, from.methodology),
+            (&mut audit.auditor, from.auditor),
+            (&mut audit.period_start, from.period_start),
+            (&mut audit.period_end, from.period_end),
+        ] {
+            if field.trim().is_empty() {
+                *field = value;
+            }
+        }
+        for control in other.controls {
+            let id = normalize_control_id(&control.id);
+            match self
+                .controls
+                .iter_mut()
+                .find(|c| normalize_control_id(&c.id) == id)
+            {
+                Some(existing) => existing.fill_from(control),
+                None => self.controls.push(control),
+            }
+        }
+        self.risks.extend(other.risks);
+        self.observations.extend(other.observations);
+        self.poam.extend(other.poam);
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditInfo {
+    pub framework: Option<String>,
+    pub scope: String,
+    pub methodology: String,
+    pub auditor: String,
+    pub period_start: String,
+    pub period_end: String,
+}
+
+/// A single control from NIST 800-53, ISO 27001, SOC 2 or a similar framework.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Control {
+    pub id: String,
+    pub title: String,
+    /// Control family; derived from the control ID when omitted.
+    pub family: Option<String>,
+    pub framework: Option<String>,
+    pub status: ControlStatus,
+    pub responsible_party: String,
+    pub implementation_description: String,
+    pub evidence: Vec<Evidence>,
+    pub findings: Vec<Finding>,
+    pub poam: Option<PoamEntry>,
+}
+
+impl Control {
+    /// Take the details of `other` where this control has none. The status
+    /// is always this control's own.
+    fn fill_from(&mut self, other: Control) {
+        for (field, value) in [
+            (&mut self.title, other.title),
+            (&mut self.responsible_party, other.responsible_party),
+            (
+                &mut self.implementation_description,
+                other.implementation_description,
+            ),
+        ] {
+            if field.trim().is_empty() {
+                *field = value;
+            }
+        }
+        self.family = self.family.take().or(other.family);
+        self.framework = self.framework.take().or(other.framework);
+        self.poam = self.poam.take().or(other.poam);
+        if self.evidence.is_empty() {
+            self.evidence = other.evidence;
+        }
+        if self.findings.is_empty() {
+            self.findings = other.findings;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", try_from = "String")]
+pub enum ControlStatus {
+    Implemented,
+    PartiallyImplemented,
+    Planned,
+    #[default]
+    NotImplemented,
+    NotApplicable,
+}
+
+impl TryFrom<String> for ControlStatus {
+    type Error = String;
+
+    /// Accepts the usual spellings across frameworks, case-insensitively
+    /// (`Implemented`, `partially-implemented`, `Satisfied`, `N/A`, ...).
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        let normalized = value.trim().to_lowercase().replace([' ', '-'], "_");
+        match normalized.as_str() {
+            "implemented" | "compliant" | "satisfied" => Ok(ControlStatus::Implemented),
+            "partially_implemented" | "partial" | "partially_compliant" => {
+                Ok(ControlStatus::PartiallyImplemented)
+            }
+            "planned" | "planned_for_implementation" => Ok(ControlStatus::Planned),
+            "not_implemented" | "non_compliant" | "not_satisfied" => {
+                Ok(ControlStatus::NotImplemented)
+            }
+            "not_applicable" | "n/a" | "na" => Ok(ControlStatus::NotApplicable),
+            _ => Err(format!("unknown control status '{}'", value)),
+        }
+    }
+}
+
+impl ControlStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControlStatus::Implemented => "Implemented",
+            ControlStatus::PartiallyImplemented => "Partially Implemented",
+            ControlStatus::Planned => "Planned",
+            ControlStatus::NotImplemented => "Not Implemented",
+            ControlStatus::NotApplicable => "Not Applicable",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Evidence {
+    pub id: Option<String>,
+    pub description: String,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Finding {
+    pub id: String,
+    pub severity: String,
+    pub description: String,
+    pub recommendation: Option<String>,
+}
+
+/// Plan of Action and Milestones entry for a control weakness.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoamEntry {
+    pub id: String,
+    pub weakness: String,
+    pub scheduled_completion: String,
+    pub status: String,
+    pub milestones: Vec<PoamMilestone>,
+    /// Controls the weakness affects, for entries not given on a control.
+    pub controls: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoamMilestone {
+    pub description: String,
+    pub due_date: String,
+}
+
+/// A risk identified by the assessment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Risk {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub status: String,
+    pub likelihood: Option<String>,
+    pub impact: Option<String>,
+    pub deadline: Option<String>,
+    pub remediation: Option<String>,
+    pub controls: Vec<String>,
+}
+
+/// An assessment observation: what was examined, interviewed or tested.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Observation {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub methods: Vec<String>,
+    pub collected: Option<String>,
+    pub controls: Vec<String>,
+}
+
+/// Control counts by status for a family or the whole audit.
+#[derive(Debug, Default, Serialize)]
+struct StatusTally {
+    total: usize,
+    applicable: usize,
+    implemented: usize,
+    partially_implemented: usize,
+    planned: usize,
+    not_implemented: usize,
+    not_applicable: usize,
+    compliance_percentage: String,
+}
+
+impl StatusTally {
+    fn add(&mut self, status: ControlStatus) {
+        self.total += 1;
+        match status {
+            ControlStatus::Implemented => self.implemented += 1,
+            ControlStatus::PartiallyImplemented => self.partially_implemented += 1,
+            ControlStatus::Planned => self.planned += 1,
+            ControlStatus::NotImplemented => self.not_implemented += 1,
+            ControlStatus::NotApplicable => self.not_applicable += 1,
+        }
+        self.applicable = self.total - self.not_applicable;
+    }
+
+    /// Share of applicable controls that are fully implemented.
+    fn finish(mut self) -> Self {
+        let percentage = if self.applicable == 0 {
+            100.0
+        } else {
+            self.implemented as f64 * 100.0 / self.applicable as f64
+        };
+        self.compliance_percentage = format!("{:.1}", percentage);
+        self
+    }
+}
+
+pub struct ComplianceReportGenerator {
+    templates: Arc<TemplateRegistry>,
+}
+
+impl ComplianceReportGenerator {
+    pub fn new(templates: Arc<TemplateRegistry>) -> Self {
+        Self { templates }
+    }
+}
+
+#[async_trait]
+impl Generator for ComplianceReportGenerator {
+    async fn generate(&self, data: &Value, metadata: &DocumentMetadata) -> Result<String> {
+        info!(
+            title = %metadata.title,
+            "Generating Compliance Audit Report"
+        );
+
+        let mut audit: ComplianceAuditData = serde_json::from_value(data.clone())
+            .map_err(|e| DocumentError::InvalidData(format!("compliance audit data: {}", e)))?;
+        if let Some(assessment) = oscal::parse(data)? {
+            audit.merge(assessment);
+        }
+
+        let context = build_context(&audit, metadata);
+
+        let rendered = self.templates.render("compliance_audit_report", &context)?;
+
+        info!(
+            title = %metadata.title,
+            controls = audit.controls.len(),
+            size_bytes = rendered.len(),
+            "Compliance audit report generated"
+        );
+
+        Ok(rendered)
+    }
+}
+
+/// Template context: per-family tallies with their controls, plus flattened
+/// findings and POA&M tables for the summary sections, and the risks and
+/// observations.
+fn build_context(audit: &ComplianceAuditData, metadata: &DocumentMetadata) -> Value {
+    let mut overall = StatusTally::default();
+    let mut families: BTreeMap<String, (StatusTally, Vec<Value>)> = BTreeMap::new();
+    let mut frameworks = BTreeSet::new();
+    let mut findings = Vec::new();
+    let mut poam = Vec::new();
+
+    for control in &audit.controls {
+        let family = control
+            .family
+            .clone()
+            .unwrap_or_else(|| control_family(&control.id));
+        let framework = control
+            .framework
+            .clone()
+            .or_else(|| audit.audit.framework.clone());
+
+        overall.add(control.status);
+        let (tally, controls) = families.entry(family).or_default();
+        tally.add(control.status);
+
+        let mut entry = json!(control);
+        entry["framework"] = json!(framework);
+        entry["status_label"] = json!(control.status.label());
+        controls.push(entry);
+
+        if let Some(framework) = framework {
+            frameworks.insert(framework);
+        }
+        for finding in &control.findings {
+            let mut row = json!(finding);
+            row["control_id"] = json!(control.id);
+            row["responsible_party"] = json!(control.responsible_party);
+            findings.push(row);
+        }
+        if let Some(entry) = &control.poam {
+            let mut row = json!(entry);
+            row["control_id"] = json!(control.id);
+            row["responsible_party"] = json!(control.responsible_party);
+            poam.push(row);
+        }
+    }
+    for entry in &audit.poam {
+        let mut row = json!(entry);
+        row["control_id"] = json!(entry.controls.join(", "));
+        poam.push(row);
+    }
+
+    let families: Vec<Value> = families
+        .into_iter()
+        .map(|(family, (tally, controls))| {
+            let mut row = json!(tally.finish());
+            row["family"] = json!(family);
+            row["controls"] = json!(controls);
+            row
+        })
+        .collect();
+
+    let overall = overall.finish();
+    let open_risks = audit
+        .risks
+        .iter()
+        .filter(|r| !r.status.eq_ignore_ascii_case("closed"))
+        .count();
+    let executive_summary = if audit.executive_summary.trim().is_empty() {
+        summary_text(&overall, findings.len(), poam.len(), open_risks)
+    } else {
+        audit.executive_summary.clone()
+    };
+
+    let mut summary = json!(overall);
+    summary["findings"] = json!(findings.len());
+    summary["poam_items"] = json!(poam.len());
+    summary["open_risks"] = json!(open_risks);
+
+    let risks: Vec<Value> = audit
+        .risks
+        .iter()
+        .map(|risk| {
+            let mut row = json!(risk);
+            row["control_ids"] = json!(risk.controls.join(", "));
+            row
+        })
+        .collect();
+    let observations: Vec<Value> = audit
+        .observations
+        .iter()
+        .map(|observation| {
+            let mut row = json!(observation);
+            row["control_ids"] = json!(observation.controls.join(", "));
+            row["method_list"] = json!(observation.methods.join(", "));
+            row
+        })
+        .collect();
+
+    json!({
+        "metadata": metadata,
+        "executive_summary": executive_summary,
+        "audit": audit.audit,
+        "frameworks": frameworks,
+        "summary": summary,
+        "families": families,
+        "findings": findings,
+        "poam": poam,
+        "risks": risks,
+        "observations": observations,
+    })
+}
+
+fn summary_text(
+    overall: &StatusTally,
+    findings: usize,
+    poam_items: usize,
+    open_risks: usize,
+) -> String {
+    format!(
+        "Controls assessed: {} ({} applicable): {} implemented, {} partially implemented, {} \
+         planned and {} not implemented. Overall compliance: {}%. Open findings: {}. POA&M \
+         items: {}. Open risks: {}.",
+        overall.total,
+        overall.applicable,
+        overall.implemented,
+        overall.partially_implemented,
+        overall.planned,
+        overall.not_implemented,
+        overall.compliance_percentage,
+        findings,
+        poam_items,
+        open_risks,
+    )
+}
+
+/// Control ID in one spelling for comparison: case and spaces are ignored
+/// and NIST enhancements written with a dot match the parenthesised form
+/// (`ac-2 (1)` and `AC-2.1` -> `AC-2(1)`).
+fn normalize_control_id(id: &str) -> String {
+    let id: String = id
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    match id.split_once('-') {
+        Some((family, rest)) => match rest.split_once('.') {
+            Some((base, enhancement)) => format!("{}-{}({})", family, base, enhancement),
+            None => id,
+        },
+        None => id,
+    }
+}
+
+/// Family prefix of a control ID: `AC-2(1)` -> `AC`, `A.5.1` -> `A.5`,
+/// `CC6.1` -> `CC6`.
+fn control_family(id: &str) -> String {
+    if let Some((family, _)) = id.split_once('-') {
+        return family.to_string();
+    }
+    match id.rsplit_once('.') {
+        Some((family, _)) => family.to_string(),
+        None => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> DocumentMetadata {
+        serde_json::from_value(json!({
+            "title": "Compliance Audit Report",
+            "project_name": "QXProveIt Platform",
+            "version": "1.0.0",
+            "author": "Audit Team",
+            "organization": "mcx Services, LLC"
+        }))
+        .unwrap()
+    }
+
+    fn control(id: &str, status: ControlStatus) -> Control {
+        Control {
+            id: id.to_string(),
+            status,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn build_context_tallies_families_and_percentages() {
+        use ControlStatus::*;
+        let audit = ComplianceAuditData {
+            controls: vec![
+                control("AC-2", Implemented),
+                control("AC-2(1)", Implemented),
+                control("AC-3", PartiallyImplemented),
+                control("AC-17", NotApplicable),
+                control("IA-5", Planned),
+                control("IA-2", NotImplemented),
+                control("SC-7", NotApplicable),
+            ],
+            ..Default::default()
+        };
+
+        let context = build_context(&audit, &metadata());
+
+        let summary = &context["summary"];
+        assert_eq!(summary["total"], 7);
+        assert_eq!(summary["applicable"], 5);
+        assert_eq!(summary["implemented"], 2);
+        assert_eq!(summary["compliance_percentage"], "40.0");
+
+        let families = context["families"].as_array().unwrap();
+        let family = |name: &str| {
+            families
+                .iter()
+                .find(|f| f["family"] == name)
+                .unwrap_or_else(|| panic!("no family {}", name))
+        };
+        assert_eq!(families.len(), 3);
+        assert_eq!(family("AC")["total"], 4);
+        assert_eq!(family("AC")["applicable"], 3);
+        assert_eq!(family("AC")["compliance_percentage"], "66.7");
+        assert_eq!(family("IA")["compliance_percentage"], "0.0");
+        // Nothing applicable counts as fully compliant
+        assert_eq!(family("SC")["compliance_percentage"], "100.0");
+    }
+
+    #[test]
+    fn merge_keeps_the_callers_control_over_the_ingested_one() {
+        let mut audit = ComplianceAuditData {
+            controls: vec![
+                Control {
+                    title: "Account Management".to_string(),
+                    ..control("ac-2 (1)", ControlStatus::Implemented)
+                },
+                control("AC-3", ControlStatus::Implemented),
+            ],
+            ..Default::default()
+        };
+        let ingested = ComplianceAuditData {
+            controls: vec![
+                Control {
+                    title: "Automated Account Management".to_string(),
+                    responsible_party: "IAM team".to_string(),
+                    evidence: vec![Evidence {
+                        id: Some("OBS-1".to_string()),
+                        description: "Provisioning workflow".to_string(),
+                        location: None,
+                    }],
+                    ..control("AC-2(1)", ControlStatus::NotImplemented)
+                },
+                control("AC-3", ControlStatus::NotImplemented),
+                control("AC-6", ControlStatus::NotImplemented),
+            ],
+            ..Default::default()
+        };
+
+        audit.merge(ingested);
+
+        let ids: Vec<&str> = audit.controls.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["ac-2 (1)", "AC-3", "AC-6"]);
+        let account = &audit.controls[0];
+        assert_eq!(account.status, ControlStatus::Implemented);
+        assert_eq!(account.title, "Account Management");
+        assert_eq!(account.responsible_party, "IAM team");
+        assert_eq!(account.evidence.len(), 1);
+
+        let context = build_context(&audit, &metadata());
+        assert_eq!(context["summary"]["total"], 3);
+        assert_eq!(context["summary"]["compliance_percentage"], "66.7");
+    }
+
+    #[test]
+    fn normalize_control_id_matches_enhancement_spellings() {
+        assert_eq!(normalize_control_id("AC-2(1)"), "AC-2(1)");
+        assert_eq!(normalize_control_id("ac-2 (1)"), "AC-2(1)");
+        assert_eq!(normalize_control_id("AC-2.1"), "AC-2(1)");
+        assert_eq!(normalize_control_id("A.5.1"), "A.5.1");
+        assert_eq!(normalize_control_id("cc6.1"), "CC6.1");
+    }
+}
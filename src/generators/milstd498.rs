@@ -0,0 +1,226 @@
+// document-generation-service/src/generators/milstd498.rs
+
+use crate::error::Result;
+use crate::generators::template::template_context;
+use crate::generators::{Generator, TemplateRegistry};
+use crate::models::DocumentMetadata;
+use async_trait::async_trait;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::info;
+
+/// Section 3 requirement lists and the DI-IPSC-81433A paragraph each occupies.
+const REQUIREMENT_SECTIONS: &[(&str, &str)] = &[
+    ("internal_interface_requirements", "3.4"),
+    ("internal_data_requirements", "3.5"),
+    ("adaptation_requirements", "3.6"),
+    ("safety_requirements", "3.7"),
+    ("security_requirements", "3.8"),
+    ("environment_requirements", "3.9"),
+    ("computer_resource_requirements", "3.10"),
+    ("quality_factors", "3.11"),
+    ("design_constraints", "3.12"),
+    ("personnel_requirements", "3.13"),
+    ("training_requirements", "3.14"),
+    ("logistics_requirements", "3.15"),
+    ("other_requirements", "3.16"),
+    ("packaging_requirements", "3.17"),
+];
+
+pub struct MilStd498Generator {
+    templates: Arc<TemplateRegistry>,
+}
+
+impl MilStd498Generator {
+    pub fn new(templates: Arc<TemplateRegistry>) -> Self {
+        Self { templates }
+    }
+}
+
+#[async_trait]
+impl Generator for MilStd498Generator {
+    async fn generate(&self, data: &Value, metadata: &DocumentMetadata) -> Result<String> {
+        info!(
+            title = %metadata.title,
+            "Generating MIL-STD-498 Software Requirements Specification"
+        );
+
+        let mut context = template_context("milstd498_srs", data, metadata);
+
+        if let Value::Object(ref mut ctx_map) = context {
+            number_paragraphs(ctx_map, "capabilities", "3.2");
+            number_paragraphs(ctx_map, "external_interfaces", "3.3");
+
+            let matrix = match ctx_map.get("qualification_provisions") {
+                Some(Value::Array(provisions)) => provisions.iter().map(qualification_row).collect(),
+                _ => derive_qualification_matrix(ctx_map),
+            };
+            ctx_map.insert("qualification_matrix".to_string(), Value::Array(matrix));
+
+            if !ctx_map.contains_key("traceability") {
+                let traceability = derive_traceability(ctx_map);
+                ctx_map.insert("traceability".to_string(), Value::Array(traceability));
+            }
+            let system_to_csci = invert_traceability(ctx_map.get("traceability"));
+            ctx_map.insert("system_to_csci".to_string(), Value::Array(system_to_csci));
+        }
+
+        let rendered = self.templates.render("milstd498_srs", &context)?;
+
+        info!(
+            title = %metadata.title,
+            size_bytes = rendered.len(),
+            "MIL-STD-498 SRS document generated"
+        );
+
+        Ok(rendered)
+    }
+}
+
+/// Give each entry of a numbered subsection list its paragraph number
+/// (e.g. `3.2.1`) and flatten list-valued qualification methods for display.
+fn number_paragraphs(ctx: &mut Map<String, Value>, key: &str, parent: &str) {
+    if let Some(Value::Array(items)) = ctx.get_mut(key) {
+        for (i, item) in items.iter_mut().enumerate() {
+            if let Value::Object(obj) = item {
+                obj.insert("paragraph".to_string(), json!(format!("{}.{}", parent, i + 1)));
+
+                if let Some(Value::Array(requirements)) = obj.get_mut("requirements") {
+                    for requirement in requirements.iter_mut() {
+                        if let Some(method) = requirement.get_mut("qualification_method") {
+                            if let Value::Array(methods) = method {
+                                let joined = methods
+                                    .iter()
+                                    .filter_map(Value::as_str)
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                *method = json!(joined);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Build qualification rows from every requirement that names a method.
+fn derive_qualification_matrix(ctx: &Map<String, Value>) -> Vec<Value> {
+    let mut rows = Vec::new();
+
+    for key in ["capabilities", "external_interfaces"] {
+        for group in ctx.get(key).and_then(Value::as_array).into_iter().flatten() {
+            let paragraph = group.get("paragraph").cloned().unwrap_or(Value::Null);
+            for requirement in group
+                .get("requirements")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(row) = requirement_row(requirement, &paragraph) {
+                    rows.push(row);
+                }
+            }
+        }
+    }
+
+    for (key, paragraph) in REQUIREMENT_SECTIONS {
+        for requirement in ctx.get(*key).and_then(Value::as_array).into_iter().flatten() {
+            if let Some(row) = requirement_row(requirement, &json!(paragraph)) {
+                rows.push(row);
+            }
+        }
+    }
+
+    rows
+}
+
+fn requirement_row(requirement: &Value, paragraph: &Value) -> Option<Value> {
+    let method = requirement.get("qualification_method")?;
+    Some(qualification_row(&json!({
+        "requirement_id": requirement.get("id"),
+        "paragraph": paragraph,
+        "methods": method,
+    })))
+}
+
+/// Expand a provision's methods (names, initials, or a comma-separated
+/// string) into one flag per qualification method column.
+fn qualification_row(provision: &Value) -> Value {
+    let methods: Vec<String> = match provision.get("methods").or_else(|| provision.get("method")) {
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(value)) => value.split(',').map(|m| m.trim().to_string()).collect(),
+        _ => Vec::new(),
+    };
+    let has = |initial: char| {
+        methods
+            .iter()
+            .any(|m| m.chars().next().map(|c| c.to_ascii_lowercase()) == Some(initial))
+    };
+
+    json!({
+        "requirement_id": provision.get("requirement_id"),
+        "paragraph": provision.get("paragraph"),
+        "demonstration": has('d'),
+        "test": has('t'),
+        "analysis": has('a'),
+        "inspection": has('i'),
+        "special": has('s'),
+    })
+}
+
+/// Collect CSCI-to-system traces from `system_requirements` on each capability
+/// requirement when no explicit traceability table is supplied.
+fn derive_traceability(ctx: &Map<String, Value>) -> Vec<Value> {
+    ctx.get("capabilities")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .flat_map(|c| c.get("requirements").and_then(Value::as_array).into_iter().flatten())
+        .filter_map(|r| {
+            let parents = r.get("system_requirements")?;
+            Some(json!({
+                "csci_requirement": r.get("id"),
+                "system_requirements": parents,
+            }))
+        })
+        .collect()
+}
+
+/// Invert CSCI-to-system traces into system-to-CSCI rows, ordered by ID.
+fn invert_traceability(traceability: Option<&Value>) -> Vec<Value> {
+    let mut by_system: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+
+    for trace in traceability.and_then(Value::as_array).into_iter().flatten() {
+        let Some(csci) = trace.get("csci_requirement") else {
+            continue;
+        };
+        for system in trace
+            .get("system_requirements")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            by_system
+                .entry(system.to_string())
+                .or_default()
+                .push(csci.clone());
+        }
+    }
+
+    by_system
+        .into_iter()
+        .map(|(system, csci)| {
+            json!({
+                "system_requirement": system,
+                "csci_requirements": csci,
+            })
+        })
+        .collect()
+}
@@ -1,49 +1,58 @@
 // document-generation-service/src/generators/mod.rs
 
-mod ieee830;
-mod iso29148_conops;
-mod iso29148_srs;
-mod iso29148_stakrs;
-mod iso29148_syrs;
-mod security_report;
+pub mod compliance_report;
+mod milstd498;
+mod normalize;
+mod registry;
+pub mod security_report;
+mod template;
+pub mod test_report;
+
+#[cfg(test)]
+mod tests;
 
 use crate::error::Result;
 use crate::models::{DocumentMetadata, SpecificationType};
 use async_trait::async_trait;
 use serde_json::Value;
+use std::sync::Arc;
 
-pub use ieee830::IEEE830Generator;
-pub use iso29148_conops::ISO29148ConOpsGenerator;
-pub use iso29148_srs::ISO29148SRSGenerator;
-pub use iso29148_stakrs::ISO29148StakRSGenerator;
-pub use iso29148_syrs::ISO29148SyRSGenerator;
+pub use compliance_report::ComplianceReportGenerator;
+pub use milstd498::MilStd498Generator;
+pub use normalize::normalize;
+pub use registry::{PdfLayout, TemplateRegistry, Theme};
 pub use security_report::SecurityReportGenerator;
+pub use template::TemplateGenerator;
+pub use test_report::TestReportGenerator;
 
 #[async_trait]
 pub trait Generator: Send + Sync {
     async fn generate(&self, data: &Value, metadata: &DocumentMetadata) -> Result<String>;
 }
 
-pub fn create_generator(spec_type: &SpecificationType) -> Result<Box<dyn Generator>> {
+/// Generators that derive extra context from the data; every other type in
+/// the template manifest renders through `TemplateGenerator`.
+pub fn create_generator(
+    spec_type: &SpecificationType,
+    templates: &Arc<TemplateRegistry>,
+) -> Result<Box<dyn Generator>> {
     match spec_type {
-        SpecificationType::IEEE830DRD | SpecificationType::IEEE830SRS => {
-            Ok(Box::new(IEEE830Generator::new()))
-        }
-        SpecificationType::ISO29148StakeholderRequirements => {
-            Ok(Box::new(ISO29148StakRSGenerator::new()))
+        SpecificationType::MilStd498SRS => Ok(Box::new(MilStd498Generator::new(templates.clone()))),
+        SpecificationType::ComplianceAuditReport => {
+            Ok(Box::new(ComplianceReportGenerator::new(templates.clone())))
         }
-        SpecificationType::ISO29148SystemRequirements => {
-            Ok(Box::new(ISO29148SyRSGenerator::new()))
+        SpecificationType::SecurityScanReport => {
+            Ok(Box::new(SecurityReportGenerator::new(templates.clone())))
         }
-        SpecificationType::ISO29148SoftwareRequirements => {
-            Ok(Box::new(ISO29148SRSGenerator::new()))
+        SpecificationType::TestExecutionReport => {
+            Ok(Box::new(TestReportGenerator::new(templates.clone())))
         }
-        SpecificationType::ISO29148ConceptOfOperations => {
-            Ok(Box::new(ISO29148ConOpsGenerator::new()))
+        _ => {
+            templates.entry(spec_type.as_str())?;
+            Ok(Box::new(TemplateGenerator::new(
+                templates.clone(),
+                spec_type.as_str(),
+            )))
         }
-        SpecificationType::SecurityScanReport => Ok(Box::new(SecurityReportGenerator::new())),
-        _ => Err(crate::error::DocumentError::InvalidSpecificationType(
-            format!("{:?}", spec_type),
-        )),
     }
 }
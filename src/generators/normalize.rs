@@ -0,0 +1,158 @@
+// document-generation-service/src/generators/normalize.rs
+
+//! Maps the request data shapes documented in the README onto the fields the
+//! templates read. Fields the caller already supplies under the template's
+//! own name are left alone, so template-shaped data keeps working.
+
+use serde_json::{json, Map, Value};
+
+/// Normalized copy of `data` for `spec_type`; other types pass through.
+pub fn normalize(spec_type: &str, data: &Value) -> Value {
+    let mut data = data.clone();
+    if let Value::Object(map) = &mut data {
+        match spec_type {
+            "iso29148_software_requirements" => iso29148_srs(map),
+            "iso29148_stakeholder_requirements" => iso29148_stakrs(map),
+            "iso29148_concept_of_operations" => iso29148_conops(map),
+            "security_scan_report" => security_report(map),
+            _ => {}
+        }
+    }
+    data
+}
+
+/// `introduction.*` moves to the top level; requirement `description` and
+/// `category` become `statement` and `type`.
+fn iso29148_srs(data: &mut Map<String, Value>) {
+    lift(
+        data,
+        "introduction",
+        &["purpose", "scope", "definitions", "references"],
+    );
+
+    for requirement in objects_mut(data, "requirements") {
+        copy_field(requirement, "description", "statement");
+        copy_field(requirement, "category", "type");
+    }
+}
+
+/// `business_requirements` become `business_objectives` and
+/// `stakeholder_requirements` become `requirements`.
+fn iso29148_stakrs(data: &mut Map<String, Value>) {
+    if !has(data, "business_objectives") {
+        if let Some(Value::Array(items)) = data.get("business_requirements") {
+            let objectives = items
+                .iter()
+                .cloned()
+                .map(|mut item| {
+                    if let Some(criteria @ Value::String(_)) = item.get("success_criteria") {
+                        item["success_criteria"] = json!([criteria]);
+                    }
+                    item
+                })
+                .collect();
+            data.insert("business_objectives".to_string(), Value::Array(objectives));
+        }
+    }
+
+    if !has(data, "requirements") {
+        if let Some(Value::Array(items)) = data.get("stakeholder_requirements") {
+            let requirements = items
+                .iter()
+                .cloned()
+                .map(|mut item| {
+                    if let Value::Object(requirement) = &mut item {
+                        copy_field(requirement, "requirement", "description");
+                        if let Some(stakeholder @ Value::String(_)) = requirement.get("stakeholder")
+                        {
+                            let stakeholders = json!([stakeholder]);
+                            requirement.entry("stakeholders").or_insert(stakeholders);
+                        }
+                    }
+                    item
+                })
+                .collect();
+            data.insert("requirements".to_string(), Value::Array(requirements));
+        }
+    }
+}
+
+/// `current_situation` and `proposed_system` objects are flattened into the
+/// template's narrative fields, and `operational_scenarios` become `scenarios`.
+fn iso29148_conops(data: &mut Map<String, Value>) {
+    if let Some(Value::Object(current)) = data.get("current_situation").cloned() {
+        copy_from(data, &current, "background", "current_system");
+        copy_from(data, &current, "problems", "problems");
+        copy_from(data, &current, "opportunities", "opportunities");
+    }
+
+    if let Some(Value::Object(proposed)) = data.remove("proposed_system") {
+        copy_from(data, &proposed, "vision", "proposed_system");
+        copy_from(data, &proposed, "objectives", "objectives");
+        copy_from(data, &proposed, "capabilities", "capabilities");
+    }
+
+    if !has(data, "scenarios") {
+        if let Some(scenarios) = data.get("operational_scenarios").cloned() {
+            data.insert("scenarios".to_string(), scenarios);
+        }
+    }
+    for scenario in objects_mut(data, "scenarios") {
+        copy_field(scenario, "scenario_id", "id");
+    }
+}
+
+/// `scan_metadata.*` moves to the top level and vulnerability `id`/`package`
+/// become `cve_id`/`component`. Severity counts are left to
+/// `SecurityReportGenerator`, which counts after de-duplicating.
+fn security_report(data: &mut Map<String, Value>) {
+    lift(data, "scan_metadata", &["scan_date", "scanner", "target"]);
+
+    for vulnerability in objects_mut(data, "vulnerabilities") {
+        copy_field(vulnerability, "id", "cve_id");
+        copy_field(vulnerability, "package", "component");
+    }
+}
+
+fn has(map: &Map<String, Value>, key: &str) -> bool {
+    map.get(key).is_some_and(|v| !v.is_null())
+}
+
+/// Copy `from` to `to` within an object unless `to` is already set.
+fn copy_field(map: &mut Map<String, Value>, from: &str, to: &str) {
+    if !has(map, to) {
+        if let Some(value) = map.get(from).filter(|v| !v.is_null()).cloned() {
+            map.insert(to.to_string(), value);
+        }
+    }
+}
+
+/// Copy `source[from]` to `map[to]` unless `to` is already set.
+fn copy_from(map: &mut Map<String, Value>, source: &Map<String, Value>, from: &str, to: &str) {
+    if !has(map, to) {
+        if let Some(value) = source.get(from).filter(|v| !v.is_null()) {
+            map.insert(to.to_string(), value.clone());
+        }
+    }
+}
+
+/// Copy `fields` of the nested `section` object to the top level.
+fn lift(map: &mut Map<String, Value>, section: &str, fields: &[&str]) {
+    if let Some(Value::Object(nested)) = map.get(section).cloned() {
+        for field in fields {
+            copy_from(map, &nested, field, field);
+        }
+    }
+}
+
+/// The object elements of the array at `key`.
+fn objects_mut<'a>(
+    map: &'a mut Map<String, Value>,
+    key: &str,
+) -> impl Iterator<Item = &'a mut Map<String, Value>> {
+    map.get_mut(key)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object_mut)
+}
@@ -0,0 +1,606 @@
+// document-generation-service/src/generators/registry.rs
+
+use crate::error::{DocumentError, Result, Violation};
+use crate::marking;
+use handlebars::Handlebars;
+use jsonschema::{error::ValidationErrorKind, Validator};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use tracing::{info, warn};
+
+/// Manifest file, relative to `templates.path`.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Partials directory, relative to `templates.path`.
+const PARTIALS_DIR: &str = "partials";
+
+/// PDF layout profiles, relative to `templates.path`.
+const LAYOUTS_FILE: &str = "layouts.json";
+
+/// HTML theme directory, relative to `templates.path`, and its catalogue.
+const THEMES_DIR: &str = "themes";
+const THEMES_FILE: &str = "themes.json";
+
+/// A document type declared in the template manifest.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TemplateEntry {
+    pub display_name: String,
+    /// Template file, relative to `templates.path`.
+    pub template: String,
+    /// Fields that must be present (and non-null) in the request data;
+    /// nested fields are written with dots, e.g. `introduction.purpose`.
+    #[serde(default)]
+    pub required_fields: Vec<String>,
+    /// Partials from `partials/` the template uses.
+    #[serde(default)]
+    pub partials: Vec<String>,
+    /// JSON Schema for the request data, relative to `templates.path`.
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// PDF layout profile from `layouts.json`; the default profile when unset.
+    #[serde(default)]
+    pub layout: Option<String>,
+}
+
+/// `layouts.json`: the named PDF layout profiles and the default one.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct LayoutCatalog {
+    default: Option<String>,
+    profiles: HashMap<String, PdfLayout>,
+}
+
+/// Page setup for PDF and LaTeX output. Fields a profile leaves out keep the
+/// values below: US Letter, 1in margins, 11pt `article`, numbered sections and
+/// a three-level table of contents.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PdfLayout {
+    #[serde(skip)]
+    pub name: String,
+    pub paper_size: PaperSize,
+    /// Geometry margin on all sides, e.g. `1in` or `25mm`.
+    pub margin: String,
+    pub font_size: String,
+    /// Body font, by system font name; the class's font when unset.
+    pub main_font: Option<String>,
+    pub document_class: String,
+    pub toc_depth: u8,
+    pub number_sections: bool,
+    /// Set sections headed "Appendix ..." on landscape pages.
+    pub landscape_appendices: bool,
+    /// Line spacing as a multiple of the font's; single spacing when unset.
+    pub line_spacing: Option<f32>,
+}
+
+impl Default for PdfLayout {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            paper_size: PaperSize::Letter,
+            margin: "1in".to_string(),
+            font_size: "11pt".to_string(),
+            main_font: None,
+            document_class: "article".to_string(),
+            toc_depth: 3,
+            number_sections: true,
+            landscape_appendices: false,
+            line_spacing: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaperSize {
+    Letter,
+    A4,
+}
+
+impl PaperSize {
+    /// Pandoc's `papersize` variable.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaperSize::Letter => "letter",
+            PaperSize::A4 => "a4",
+        }
+    }
+}
+
+/// `themes/themes.json`: the bundled HTML stylesheets, the default theme and
+/// the theme each tenant uses.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ThemeCatalog {
+    default: Option<String>,
+    themes: HashMap<String, ThemeEntry>,
+    /// Theme name by tenant ID.
+    #[serde(default)]
+    tenants: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ThemeEntry {
+    /// Stylesheet file, relative to `themes/`.
+    stylesheet: String,
+    version: String,
+}
+
+/// A bundled stylesheet chosen for an HTML render.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub version: String,
+    /// Absolute path of the stylesheet, embedded by Pandoc.
+    pub stylesheet: PathBuf,
+}
+
+/// Manifest entries with their templates and data schemas, compiled together.
+struct Compiled {
+    entries: HashMap<String, TemplateEntry>,
+    handlebars: Handlebars<'static>,
+    schemas: HashMap<String, Validator>,
+    themes: HashMap<String, Theme>,
+    default_theme: Option<String>,
+    tenant_themes: HashMap<String, String>,
+    layouts: HashMap<String, PdfLayout>,
+    default_layout: Option<String>,
+}
+
+/// Document types keyed by specification type, loaded from the manifest in
+/// the template directory and compiled once. Renders share a read lock; only
+/// a hot reload takes the write lock.
+pub struct TemplateRegistry {
+    root: PathBuf,
+    compiled: RwLock<Compiled>,
+}
+
+impl TemplateRegistry {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let root = path.as_ref().to_path_buf();
+        let compiled = compile(&root)?;
+
+        info!(
+            path = %root.display(),
+            document_types = compiled.entries.len(),
+            "Compiled templates"
+        );
+
+        Ok(Self {
+            root,
+            compiled: RwLock::new(compiled),
+        })
+    }
+
+    pub fn entry(&self, spec_type: &str) -> Result<TemplateEntry> {
+        self.read()
+            .entries
+            .get(spec_type)
+            .cloned()
+            .ok_or_else(|| DocumentError::InvalidSpecificationType(spec_type.to_string()))
+    }
+
+    /// Check `data` against the entry's required fields and schema, returning
+    /// every violation found (empty when the data is valid).
+    pub fn validate(&self, spec_type: &str, data: &Value) -> Result<Vec<Violation>> {
+        let compiled = self.read();
+        let entry = compiled
+            .entries
+            .get(spec_type)
+            .ok_or_else(|| DocumentError::InvalidSpecificationType(spec_type.to_string()))?;
+
+        let mut violations: Vec<Violation> = entry
+            .required_fields
+            .iter()
+            .map(|field| format!("/{}", field.replace('.', "/")))
+            .filter(|pointer| data.pointer(pointer).is_none_or(Value::is_null))
+            .map(|pointer| Violation {
+                path: pointer,
+                keyword: "required".to_string(),
+                message: "required field is missing".to_string(),
+            })
+            .collect();
+        let Some(schema) = compiled.schemas.get(spec_type) else {
+            return Ok(violations);
+        };
+
+        let schema_violations: Vec<Violation> = schema
+            .iter_errors(data)
+            .map(|error| {
+                let mut path = error.instance_path().to_string();
+                let message = match error.kind() {
+                    ValidationErrorKind::Required { property } => {
+                        let field = property.as_str().unwrap_or_default();
+                        path = format!("{}/{}", path, field);
+                        "required field is missing".to_string()
+                    }
+                    _ => error.to_string(),
+                };
+                Violation {
+                    path,
+                    keyword: error.kind().keyword().to_string(),
+                    message,
+                }
+            })
+            .collect();
+        // A field listed in both the manifest and the schema is reported once
+        for violation in schema_violations {
+            if !violations
+                .iter()
+                .any(|v| v.path == violation.path && v.keyword == violation.keyword)
+            {
+                violations.push(violation);
+            }
+        }
+
+        Ok(violations)
+    }
+
+    /// Render the template compiled for `spec_type`.
+    pub fn render<T: Serialize>(&self, spec_type: &str, context: &T) -> Result<String> {
+        let compiled = self.read();
+        if !compiled.entries.contains_key(spec_type) {
+            return Err(DocumentError::InvalidSpecificationType(
+                spec_type.to_string(),
+            ));
+        }
+        Ok(compiled.handlebars.render(spec_type, context)?)
+    }
+
+    /// Stylesheet for HTML output: the `requested` theme, else the tenant's
+    /// theme, else the default. `None` when no themes are bundled.
+    pub fn theme(&self, requested: Option<&str>, tenant: Option<&str>) -> Result<Option<Theme>> {
+        let compiled = self.read();
+        if let Some(name) = requested {
+            return compiled
+                .themes
+                .get(name)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| DocumentError::InvalidData(format!("unknown theme '{}'", name)));
+        }
+        let name = tenant
+            .and_then(|tenant| compiled.tenant_themes.get(tenant))
+            .or(compiled.default_theme.as_ref());
+        Ok(name.and_then(|name| compiled.themes.get(name)).cloned())
+    }
+
+    /// PDF layout profile: `requested` when given (normally the request's,
+    /// else its template's), otherwise the default profile.
+    pub fn pdf_layout(&self, requested: Option<&str>) -> Result<PdfLayout> {
+        let compiled = self.read();
+        match requested.or(compiled.default_layout.as_deref()) {
+            Some(name) => compiled.layouts.get(name).cloned().ok_or_else(|| {
+                DocumentError::InvalidData(format!("unknown layout profile '{}'", name))
+            }),
+            None => Ok(PdfLayout::default()),
+        }
+    }
+
+    /// Recompile the manifest and templates from disk. On failure the
+    /// previously compiled set stays in service.
+    pub fn reload(&self) -> Result<()> {
+        let compiled = compile(&self.root)?;
+        let document_types = compiled.entries.len();
+        *self
+            .compiled
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = compiled;
+
+        info!(
+            path = %self.root.display(),
+            document_types,
+            "Reloaded templates"
+        );
+
+        Ok(())
+    }
+
+    /// Reload whenever anything under the template directory changes. The
+    /// returned watcher must be kept alive for as long as reloading is wanted.
+    pub fn watch(self: &Arc<Self>) -> notify::Result<RecommendedWatcher> {
+        let registry = Arc::clone(self);
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event)
+                    if matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) =>
+                {
+                    if let Err(e) = registry.reload() {
+                        warn!("Template reload failed, keeping previous templates: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Template watch error: {}", e),
+            })?;
+        watcher.watch(&self.root, RecursiveMode::Recursive)?;
+
+        info!(path = %self.root.display(), "Watching templates for changes");
+
+        Ok(watcher)
+    }
+
+    /// Make missing and empty placeholders visible in rendered output, so
+    /// tests can assert that every field a template reads was supplied.
+    #[cfg(test)]
+    pub fn mark_empty_placeholders(&self) {
+        let mut compiled = self.compiled.write().unwrap();
+        compiled.handlebars.register_helper(
+            "helperMissing",
+            Box::new(
+                |h: &handlebars::Helper,
+                 _: &Handlebars,
+                 _: &handlebars::Context,
+                 _: &mut handlebars::RenderContext,
+                 out: &mut dyn handlebars::Output|
+                 -> handlebars::HelperResult {
+                    out.write(&format!("<<missing {}>>", h.name()))?;
+                    Ok(())
+                },
+            ),
+        );
+        compiled.handlebars.register_escape_fn(|value| {
+            if value.trim().is_empty() {
+                "<<empty>>".to_string()
+            } else {
+                value.to_string()
+            }
+        });
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Compiled> {
+        self.compiled
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Read the manifest under `root` and compile every template, partial and
+/// schema it names; templates are registered under their specification type.
+fn compile(root: &Path) -> Result<Compiled> {
+    let manifest = std::fs::read_to_string(root.join(MANIFEST_FILE))?;
+    let entries: HashMap<String, TemplateEntry> = serde_json::from_str(&manifest)?;
+
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(false);
+    handlebars.register_helper("portion", Box::new(marking::portion_helper));
+    let mut schemas = HashMap::new();
+
+    for (spec_type, entry) in &entries {
+        for partial in &entry.partials {
+            if handlebars.has_template(partial) {
+                continue;
+            }
+            let partial_path = root.join(PARTIALS_DIR).join(format!("{}.md.hbs", partial));
+            let source = std::fs::read_to_string(&partial_path)
+                .map_err(|_| DocumentError::TemplateNotFound(partial_path.display().to_string()))?;
+            handlebars.register_partial(partial, source)?;
+        }
+
+        handlebars.register_template_file(spec_type, root.join(&entry.template))?;
+
+        if let Some(schema) = &entry.schema {
+            let schema_path = root.join(schema);
+            let source = std::fs::read_to_string(&schema_path)
+                .map_err(|_| DocumentError::TemplateNotFound(schema_path.display().to_string()))?;
+            let validator =
+                jsonschema::validator_for(&serde_json::from_str(&source)?).map_err(|e| {
+                    DocumentError::InvalidData(format!("{}: {}", schema_path.display(), e))
+                })?;
+            schemas.insert(spec_type.clone(), validator);
+        }
+    }
+
+    let layouts_path = root.join(LAYOUTS_FILE);
+    let layout_catalog: LayoutCatalog = if layouts_path.exists() {
+        serde_json::from_str(&std::fs::read_to_string(&layouts_path)?)?
+    } else {
+        LayoutCatalog::default()
+    };
+    let mut layouts = layout_catalog.profiles;
+    for (name, layout) in layouts.iter_mut() {
+        layout.name = name.clone();
+    }
+    let layout_references = entries.values().filter_map(|entry| entry.layout.as_ref());
+    for name in layout_catalog.default.iter().chain(layout_references) {
+        if !layouts.contains_key(name) {
+            return Err(DocumentError::InvalidData(format!(
+                "{}: unknown layout profile '{}'",
+                layouts_path.display(),
+                name
+            )));
+        }
+    }
+
+    let catalog_path = root.join(THEMES_DIR).join(THEMES_FILE);
+    let catalog: ThemeCatalog = if catalog_path.exists() {
+        serde_json::from_str(&std::fs::read_to_string(&catalog_path)?)?
+    } else {
+        ThemeCatalog::default()
+    };
+    let mut themes = HashMap::new();
+    for (name, entry) in catalog.themes {
+        let path = root.join(THEMES_DIR).join(&entry.stylesheet);
+        let stylesheet = path
+            .canonicalize()
+            .map_err(|_| DocumentError::TemplateNotFound(path.display().to_string()))?;
+        themes.insert(
+            name.clone(),
+            Theme {
+                name,
+                version: entry.version,
+                stylesheet,
+            },
+        );
+    }
+    for name in catalog.default.iter().chain(catalog.tenants.values()) {
+        if !themes.contains_key(name) {
+            return Err(DocumentError::InvalidData(format!(
+                "{}: unknown theme '{}'",
+                catalog_path.display(),
+                name
+            )));
+        }
+    }
+
+    Ok(Compiled {
+        entries,
+        handlebars,
+        schemas,
+        themes,
+        default_theme: catalog.default,
+        tenant_themes: catalog.tenants,
+        layouts,
+        default_layout: layout_catalog.default,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A template directory with one document type and no layouts or themes.
+    fn template_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(MANIFEST_FILE),
+            r#"{"note": {"display_name": "Note", "template": "note.md.hbs"}}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("note.md.hbs"), "# {{title}}\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn pdf_layout_resolves_profiles_by_name() {
+        let templates = TemplateRegistry::load("./templates").unwrap();
+
+        let layout = templates.pdf_layout(Some("a4_report")).unwrap();
+        assert_eq!(layout.name, "a4_report");
+        assert_eq!(layout.paper_size, PaperSize::A4);
+        assert_eq!(layout.margin, "25mm");
+        assert_eq!(layout.document_class, "scrreprt");
+        assert!(layout.landscape_appendices);
+
+        let layout = templates.pdf_layout(None).unwrap();
+        assert_eq!(layout.name, "letter");
+        assert_eq!(layout.paper_size, PaperSize::Letter);
+
+        assert!(matches!(
+            templates.pdf_layout(Some("tabloid")),
+            Err(DocumentError::InvalidData(message)) if message.contains("'tabloid'")
+        ));
+    }
+
+    #[test]
+    fn pdf_layout_falls_back_to_the_builtin_profile_without_layouts() {
+        let dir = template_dir();
+        let templates = TemplateRegistry::load(dir.path()).unwrap();
+
+        let layout = templates.pdf_layout(None).unwrap();
+        assert_eq!(layout.name, "default");
+        assert_eq!(layout.paper_size, PaperSize::Letter);
+        assert_eq!(layout.margin, "1in");
+        assert!(!layout.landscape_appendices);
+        assert!(templates.pdf_layout(Some("a4")).is_err());
+    }
+
+    #[test]
+    fn theme_prefers_the_request_then_the_tenant_then_the_default() {
+        let dir = template_dir();
+        let themes = dir.path().join(THEMES_DIR);
+        std::fs::create_dir(&themes).unwrap();
+        std::fs::write(themes.join("standard.css"), "body {}").unwrap();
+        std::fs::write(themes.join("acme.css"), "body {}").unwrap();
+        std::fs::write(
+            themes.join(THEMES_FILE),
+            r#"{
+                "default": "standard",
+                "themes": {
+                    "standard": {"stylesheet": "standard.css", "version": "1.0.0"},
+                    "acme": {"stylesheet": "acme.css", "version": "2.1.0"}
+                },
+                "tenants": {"acme-corp": "acme"}
+            }"#,
+        )
+        .unwrap();
+        let templates = TemplateRegistry::load(dir.path()).unwrap();
+        let theme_name = |requested, tenant| {
+            templates
+                .theme(requested, tenant)
+                .unwrap()
+                .map(|theme| theme.name)
+        };
+
+        assert_eq!(theme_name(None, None).as_deref(), Some("standard"));
+        assert_eq!(
+            theme_name(None, Some("globex")).as_deref(),
+            Some("standard")
+        );
+        assert_eq!(theme_name(None, Some("acme-corp")).as_deref(), Some("acme"));
+        assert_eq!(
+            theme_name(Some("standard"), Some("acme-corp")).as_deref(),
+            Some("standard")
+        );
+        let acme = templates.theme(None, Some("acme-corp")).unwrap().unwrap();
+        assert_eq!(acme.version, "2.1.0");
+        assert_eq!(
+            acme.stylesheet,
+            themes.join("acme.css").canonicalize().unwrap()
+        );
+        assert!(matches!(
+            templates.theme(Some("neon"), Some("acme-corp")),
+            Err(DocumentError::InvalidData(message)) if message.contains("'neon'")
+        ));
+    }
+
+    #[test]
+    fn theme_is_none_without_a_theme_catalogue() {
+        let dir = template_dir();
+        let templates = TemplateRegistry::load(dir.path()).unwrap();
+
+        assert!(templates.theme(None, Some("acme-corp")).unwrap().is_none());
+        assert!(templates.theme(Some("standard"), None).is_err());
+    }
+
+    #[test]
+    fn reload_serves_the_edited_templates() {
+        let dir = template_dir();
+        let templates = TemplateRegistry::load(dir.path()).unwrap();
+        let context = serde_json::json!({ "title": "Release Notes" });
+        assert_eq!(
+            templates.render("note", &context).unwrap(),
+            "# Release Notes\n"
+        );
+
+        std::fs::write(dir.path().join("note.md.hbs"), "## {{title}} (draft)\n").unwrap();
+        templates.reload().unwrap();
+
+        assert_eq!(
+            templates.render("note", &context).unwrap(),
+            "## Release Notes (draft)\n"
+        );
+    }
+
+    #[test]
+    fn failed_reload_keeps_the_previous_templates() {
+        let dir = template_dir();
+        let templates = TemplateRegistry::load(dir.path()).unwrap();
+        let context = serde_json::json!({ "title": "Release Notes" });
+
+        std::fs::write(dir.path().join("note.md.hbs"), "# {{title}\n").unwrap();
+        assert!(templates.reload().is_err());
+        std::fs::write(dir.path().join(MANIFEST_FILE), "{").unwrap();
+        assert!(templates.reload().is_err());
+
+        assert_eq!(
+            templates.render("note", &context).unwrap(),
+            "# Release Notes\n"
+        );
+        assert!(templates.entry("note").is_ok());
+    }
+}
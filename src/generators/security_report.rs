@@ -1,40 +1,165 @@
 // document-generation-service/src/generators/security_report.rs
 
-use crate::error::Result;
-use crate::generators::Generator;
+use crate::error::{DocumentError, Result};
+use crate::generators::{normalize, Generator, TemplateRegistry};
+use crate::ingest::{cyclonedx, grype, sarif, spdx, trivy};
 use crate::models::DocumentMetadata;
 use async_trait::async_trait;
-use handlebars::Handlebars;
-use serde_json::Value;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use tracing::info;
 
-pub struct SecurityReportGenerator {
-    handlebars: Arc<RwLock<Handlebars<'static>>>,
+/// Security scan data. Findings are given directly as `vulnerabilities`, or
+/// as raw scanner output in `trivy_json`, `grype_json` and `sarif`; each of
+/// those, like the CycloneDX or SPDX documents in `sbom`, holds one report or
+/// a list of them, either as JSON or as a base64-encoded file.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SecurityScanData {
+    pub executive_summary: String,
+    pub scan_date: Option<String>,
+    pub scanner: Option<String>,
+    pub target: Option<String>,
+    /// Caller-supplied counts; computed from the findings when absent or when
+    /// scanner output is given.
+    pub summary: Option<SeverityCounts>,
+    pub vulnerabilities: Vec<Vulnerability>,
+    pub recommendations: Vec<String>,
+    pub trivy_json: Option<Value>,
+    pub grype_json: Option<Value>,
+    pub sarif: Option<Value>,
+    pub sbom: Option<Value>,
 }
 
-impl SecurityReportGenerator {
-    pub fn new() -> Self {
-        let mut handlebars = Handlebars::new();
-        handlebars.set_strict_mode(false);
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeverityCounts {
+    pub total_vulnerabilities: usize,
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Vulnerability {
+    pub cve_id: String,
+    pub title: Option<String>,
+    pub severity: Severity,
+    pub cvss_score: Option<f64>,
+    pub description: Option<String>,
+    pub impact: Option<String>,
+    pub remediation: Option<String>,
+    pub references: Vec<String>,
+    pub packages: Vec<AffectedPackage>,
+    /// Single affected package, as hand-written data gives it; folded into
+    /// `packages` before rendering.
+    #[serde(skip_serializing)]
+    pub component: Option<String>,
+    #[serde(skip_serializing)]
+    pub version: Option<String>,
+    #[serde(skip_serializing)]
+    pub fixed_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AffectedPackage {
+    pub name: String,
+    pub version: Option<String>,
+    pub fixed_version: Option<String>,
+    /// Package URL, used to match the package to SBOM components.
+    pub purl: Option<String>,
+}
 
-        Self {
-            handlebars: Arc::new(RwLock::new(handlebars)),
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "Option<String>")]
+pub enum Severity {
+    #[default]
+    Unknown,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl From<String> for Severity {
+    /// Accepts the scanners' spellings case-insensitively; Grype's
+    /// `Negligible` counts as low.
+    fn from(value: String) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "critical" => Severity::Critical,
+            "high" => Severity::High,
+            "medium" | "moderate" => Severity::Medium,
+            "low" | "negligible" => Severity::Low,
+            _ => Severity::Unknown,
         }
     }
+}
 
-    async fn load_template(&self, template_name: &str) -> Result<()> {
-        let template_path = format!("./templates/{}.md.hbs", template_name);
-        let mut hb = self.handlebars.write().await;
+impl From<Option<String>> for Severity {
+    /// A missing or `null` severity, as Grype and SARIF tools emit, is unknown.
+    fn from(value: Option<String>) -> Self {
+        value.map_or(Severity::Unknown, Severity::from)
+    }
+}
 
-        match hb.register_template_file(template_name, &template_path) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(crate::error::DocumentError::TemplateError(e)),
+impl Severity {
+    /// Qualitative rating of a CVSS v3 base score.
+    pub fn from_cvss(score: f64) -> Self {
+        match score {
+            s if s >= 9.0 => Severity::Critical,
+            s if s >= 7.0 => Severity::High,
+            s if s >= 4.0 => Severity::Medium,
+            s if s > 0.0 => Severity::Low,
+            _ => Severity::Unknown,
         }
     }
 }
 
+/// One entry of a software bill of materials.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Component {
+    pub name: String,
+    pub version: Option<String>,
+    pub license: Option<String>,
+    pub purl: Option<String>,
+    pub supplier: Option<String>,
+}
+
+/// Components read from one SBOM document.
+#[derive(Debug, Default)]
+pub struct Sbom {
+    /// Format and specification version, e.g. `CycloneDX 1.5`.
+    pub format: String,
+    /// What the SBOM describes, e.g. the scanned image.
+    pub subject: Option<String>,
+    pub components: Vec<Component>,
+}
+
+/// Findings and scan details read from one scanner report.
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    pub scanner: Option<String>,
+    pub target: Option<String>,
+    pub scan_date: Option<String>,
+    pub vulnerabilities: Vec<Vulnerability>,
+}
+
+pub struct SecurityReportGenerator {
+    templates: Arc<TemplateRegistry>,
+}
+
+impl SecurityReportGenerator {
+    pub fn new(templates: Arc<TemplateRegistry>) -> Self {
+        Self { templates }
+    }
+}
+
 #[async_trait]
 impl Generator for SecurityReportGenerator {
     async fn generate(&self, data: &Value, metadata: &DocumentMetadata) -> Result<String> {
@@ -43,33 +168,606 @@ impl Generator for SecurityReportGenerator {
             "Generating Security Scan Report"
         );
 
-        // Load template
-        self.load_template("security_report").await?;
+        let data = normalize("security_scan_report", data);
+        let mut scan: SecurityScanData = serde_json::from_value(data)
+            .map_err(|e| DocumentError::InvalidData(format!("security scan data: {}", e)))?;
 
-        // Combine metadata and data for template context
-        let mut context = serde_json::json!({
-            "metadata": metadata,
-            "data": data,
-        });
+        let mut reports = Vec::new();
+        for (field, input, parse) in [
+            (
+                "trivy_json",
+                scan.trivy_json.take(),
+                trivy::parse as fn(&Value) -> _,
+            ),
+            ("grype_json", scan.grype_json.take(), grype::parse),
+            ("sarif", scan.sarif.take(), sarif::parse),
+        ] {
+            for report in decode_reports(field, input)? {
+                reports.push(parse(&report)?);
+            }
+        }
 
-        // Merge if data is an object
-        if let Value::Object(map) = data {
-            if let Value::Object(ref mut ctx_map) = context {
-                for (key, value) in map {
-                    ctx_map.insert(key.clone(), value.clone());
-                }
+        // Counts must match the de-duplicated findings the report lists
+        if !reports.is_empty() {
+            scan.summary = None;
+        }
+
+        let mut scanners = Vec::new();
+        let mut targets = Vec::new();
+        for report in reports {
+            scanners.extend(report.scanner);
+            targets.extend(report.target);
+            if scan.scan_date.is_none() {
+                scan.scan_date = report.scan_date;
             }
+            scan.vulnerabilities.extend(report.vulnerabilities);
         }
+        scan.scanner = scan.scanner.take().or_else(|| join_unique(scanners));
+        scan.target = scan.target.take().or_else(|| join_unique(targets));
 
-        let hb = self.handlebars.read().await;
-        let rendered = hb.render("security_report", &context)?;
+        let sboms = decode_reports("sbom", scan.sbom.take())?
+            .iter()
+            .map(parse_sbom)
+            .collect::<Result<Vec<_>>>()?;
+
+        let vulnerabilities = deduplicate(std::mem::take(&mut scan.vulnerabilities));
+        let mut context = build_context(&scan, &vulnerabilities, metadata);
+        if !sboms.is_empty() {
+            context["sbom"] = sbom_context(&sboms, &vulnerabilities);
+        }
+
+        let rendered = self.templates.render("security_scan_report", &context)?;
 
         info!(
             title = %metadata.title,
+            vulnerabilities = vulnerabilities.len(),
             size_bytes = rendered.len(),
-            "Security report generated"
+            "Security scan report generated"
         );
 
         Ok(rendered)
     }
 }
+
+/// CycloneDX or SPDX, told apart by their format markers.
+fn parse_sbom(document: &Value) -> Result<Sbom> {
+    if document.get("bomFormat").and_then(Value::as_str) == Some("CycloneDX") {
+        cyclonedx::parse(document)
+    } else if document.get("spdxVersion").is_some() {
+        spdx::parse(document)
+    } else {
+        Err(DocumentError::InvalidData(
+            "sbom: not a CycloneDX or SPDX JSON document".to_string(),
+        ))
+    }
+}
+
+/// The reports in a `trivy_json`/`grype_json`/`sarif`/`sbom` field: a JSON report,
+/// a base64-encoded report file, or a list of either.
+fn decode_reports(field: &str, input: Option<Value>) -> Result<Vec<Value>> {
+    let items = match input {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Array(items)) => items,
+        Some(item) => vec![item],
+    };
+
+    items
+        .into_iter()
+        .map(|item| match item {
+            Value::String(encoded) => {
+                let content = general_purpose::STANDARD.decode(encoded.trim())?;
+                serde_json::from_slice(&content)
+                    .map_err(|e| DocumentError::InvalidData(format!("{}: {}", field, e)))
+            }
+            Value::Object(_) => Ok(item),
+            _ => Err(DocumentError::InvalidData(format!(
+                "{}: expected a report object or base64 string",
+                field
+            ))),
+        })
+        .collect()
+}
+
+/// One entry per vulnerability ID, most severe first. A CVE reported for
+/// several packages, or by several scanners, is merged into one finding that
+/// lists every affected package.
+fn deduplicate(vulnerabilities: Vec<Vulnerability>) -> Vec<Vulnerability> {
+    let mut merged: Vec<Vulnerability> = Vec::with_capacity(vulnerabilities.len());
+    // Position in `merged` of each vulnerability ID, upper-cased
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for mut vulnerability in vulnerabilities {
+        if let Some(name) = vulnerability.component.take() {
+            vulnerability.packages.push(AffectedPackage {
+                name,
+                version: vulnerability.version.take(),
+                fixed_version: vulnerability.fixed_version.take(),
+                purl: None,
+            });
+        }
+
+        if vulnerability.cve_id.is_empty() {
+            merged.push(vulnerability);
+            continue;
+        }
+        let key = vulnerability.cve_id.to_ascii_uppercase();
+        let Some(&position) = positions.get(&key) else {
+            positions.insert(key, merged.len());
+            merged.push(vulnerability);
+            continue;
+        };
+        let existing = &mut merged[position];
+
+        existing.severity = existing.severity.max(vulnerability.severity);
+        existing.cvss_score = match (existing.cvss_score, vulnerability.cvss_score) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        existing.title = existing.title.take().or(vulnerability.title);
+        existing.description = existing.description.take().or(vulnerability.description);
+        existing.impact = existing.impact.take().or(vulnerability.impact);
+        existing.remediation = existing.remediation.take().or(vulnerability.remediation);
+        for reference in vulnerability.references {
+            if !existing.references.contains(&reference) {
+                existing.references.push(reference);
+            }
+        }
+        for package in vulnerability.packages {
+            match existing
+                .packages
+                .iter_mut()
+                .find(|p| p.name == package.name && p.version == package.version)
+            {
+                Some(known) => {
+                    known.fixed_version = known.fixed_version.take().or(package.fixed_version);
+                    known.purl = known.purl.take().or(package.purl);
+                }
+                None => existing.packages.push(package),
+            }
+        }
+    }
+
+    merged.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then(
+                b.cvss_score
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.cvss_score.unwrap_or(0.0)),
+            )
+            .then_with(|| a.cve_id.cmp(&b.cve_id))
+    });
+    merged
+}
+
+/// Template context: scan details, severity counts, the executive summary
+/// numbers and the findings with their remediation and heading anchors.
+fn build_context(
+    scan: &SecurityScanData,
+    vulnerabilities: &[Vulnerability],
+    metadata: &DocumentMetadata,
+) -> Value {
+    let count = |severity: Severity| {
+        vulnerabilities
+            .iter()
+            .filter(|v| v.severity == severity)
+            .count()
+    };
+    let counts = scan.summary.clone().unwrap_or_else(|| SeverityCounts {
+        total_vulnerabilities: vulnerabilities.len(),
+        critical: count(Severity::Critical),
+        high: count(Severity::High),
+        medium: count(Severity::Medium),
+        low: count(Severity::Low),
+    });
+    let unknown = count(Severity::Unknown);
+    let fixable = vulnerabilities
+        .iter()
+        .filter(|v| v.packages.iter().any(|p| p.fixed_version.is_some()))
+        .count();
+    let mut packages: Vec<&str> = vulnerabilities
+        .iter()
+        .flat_map(|v| v.packages.iter().map(|p| p.name.as_str()))
+        .collect();
+    packages.sort_unstable();
+    packages.dedup();
+
+    let executive_summary = if scan.executive_summary.trim().is_empty() {
+        summary_text(
+            scan.target.as_deref(),
+            &counts,
+            unknown,
+            packages.len(),
+            fixable,
+        )
+    } else {
+        scan.executive_summary.clone()
+    };
+
+    let findings: Vec<Value> = vulnerabilities
+        .iter()
+        .enumerate()
+        .map(|(index, v)| {
+            let mut entry = json!(v);
+            entry["anchor"] = json!(anchor(index));
+            if v.remediation.is_none() {
+                let upgrades: Vec<String> = v
+                    .packages
+                    .iter()
+                    .filter_map(|p| {
+                        let fixed = p.fixed_version.as_ref()?;
+                        Some(format!("Upgrade {} to {} or later.", p.name, fixed))
+                    })
+                    .collect();
+                if !upgrades.is_empty() {
+                    entry["remediation"] = json!(upgrades.join(" "));
+                }
+            }
+            entry
+        })
+        .collect();
+
+    json!({
+        "metadata": metadata,
+        "executive_summary": executive_summary,
+        "scan_date": scan.scan_date,
+        "scanner": scan.scanner,
+        "target": scan.target,
+        "total_vulnerabilities": counts.total_vulnerabilities,
+        "critical_count": counts.critical,
+        "high_count": counts.high,
+        "medium_count": counts.medium,
+        "low_count": counts.low,
+        "unknown_count": unknown,
+        "fixable_count": fixable,
+        "package_count": packages.len(),
+        "vulnerabilities": findings,
+        "recommendations": scan.recommendations,
+    })
+}
+
+/// The component inventory appendix: every SBOM component with the findings
+/// against it, linked to their sections.
+fn sbom_context(sboms: &[Sbom], vulnerabilities: &[Vulnerability]) -> Value {
+    let mut components: Vec<&Component> = Vec::new();
+    for component in sboms.iter().flat_map(|s| &s.components) {
+        let listed = components.iter().any(|c| {
+            c.name == component.name && c.version == component.version && c.purl == component.purl
+        });
+        if !listed {
+            components.push(component);
+        }
+    }
+    components.sort_by_key(|c| (c.name.to_lowercase(), c.version.clone()));
+
+    let rows: Vec<Value> = components
+        .iter()
+        .map(|component| {
+            let found: Vec<Value> = vulnerabilities
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| v.packages.iter().any(|p| affects(p, component)))
+                .map(|(index, v)| {
+                    json!({
+                        "cve_id": v.cve_id,
+                        "severity": v.severity,
+                        "anchor": anchor(index),
+                    })
+                })
+                .collect();
+            let mut row = json!(component);
+            row["vulnerabilities"] = json!(found);
+            row
+        })
+        .collect();
+    let vulnerable = rows
+        .iter()
+        .filter(|row| {
+            row["vulnerabilities"]
+                .as_array()
+                .is_some_and(|v| !v.is_empty())
+        })
+        .count();
+
+    json!({
+        "sources": sboms
+            .iter()
+            .map(|s| json!({ "format": s.format, "subject": s.subject }))
+            .collect::<Vec<_>>(),
+        "component_count": rows.len(),
+        "vulnerable_count": vulnerable,
+        "clean_count": rows.len() - vulnerable,
+        "components": rows,
+    })
+}
+
+/// Whether a finding's package is this SBOM component: the same package URL
+/// (ignoring qualifiers) when both have one, otherwise the same name and
+/// version.
+fn affects(package: &AffectedPackage, component: &Component) -> bool {
+    if let (Some(a), Some(b)) = (&package.purl, &component.purl) {
+        let base = |purl: &str| {
+            purl.split(['?', '#'])
+                .next()
+                .unwrap_or_default()
+                .to_lowercase()
+        };
+        return base(a) == base(b);
+    }
+    package.name.eq_ignore_ascii_case(&component.name)
+        && match (&package.version, &component.version) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        }
+}
+
+/// Heading identifier of the `index`th finding.
+fn anchor(index: usize) -> String {
+    format!("vulnerability-{}", index + 1)
+}
+
+/// Executive summary for a report that has none. Findings of unknown
+/// severity are listed too, so the breakdown adds up to the total.
+fn summary_text(
+    target: Option<&str>,
+    counts: &SeverityCounts,
+    unknown: usize,
+    packages: usize,
+    fixable: usize,
+) -> String {
+    let breakdown = if unknown > 0 {
+        format!(
+            "{} critical, {} high, {} medium, {} low and {} unknown",
+            counts.critical, counts.high, counts.medium, counts.low, unknown
+        )
+    } else {
+        format!(
+            "{} critical, {} high, {} medium and {} low",
+            counts.critical, counts.high, counts.medium, counts.low
+        )
+    };
+    format!(
+        "{} found {} unique {} affecting {} {}: {} severity. {} of them can be remediated by \
+         upgrading to a fixed version.",
+        target.map_or("The scan".to_string(), |t| format!("The scan of {}", t)),
+        counts.total_vulnerabilities,
+        plural(
+            counts.total_vulnerabilities,
+            "vulnerability",
+            "vulnerabilities"
+        ),
+        packages,
+        plural(packages, "package", "packages"),
+        breakdown,
+        fixable,
+    )
+}
+
+/// Distinct values joined with commas; a bare name is dropped when a longer
+/// form of it is present (`Trivy` next to `Trivy v0.48.0`).
+fn join_unique(values: Vec<String>) -> Option<String> {
+    let mut unique: Vec<&String> = Vec::new();
+    for value in &values {
+        let extended = values
+            .iter()
+            .any(|other| other.starts_with(&format!("{} ", value)));
+        if !extended && !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    (!unique.is_empty()).then(|| {
+        unique
+            .iter()
+            .map(|v| v.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    })
+}
+
+fn plural<'a>(count: usize, one: &'a str, many: &'a str) -> &'a str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(
+        cve_id: &str,
+        severity: Severity,
+        cvss_score: Option<f64>,
+        package: &str,
+    ) -> Vulnerability {
+        Vulnerability {
+            cve_id: cve_id.to_string(),
+            severity,
+            cvss_score,
+            references: vec![format!("https://nvd.nist.gov/vuln/detail/{}", cve_id)],
+            packages: vec![AffectedPackage {
+                name: package.to_string(),
+                version: Some("1.0".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn severity_reads_null_and_unknown_spellings_as_unknown() {
+        let vulnerabilities: Vec<Vulnerability> = serde_json::from_value(json!([
+            { "cve_id": "CVE-2024-0001", "severity": null },
+            { "cve_id": "CVE-2024-0002", "severity": "Negligible" },
+            { "cve_id": "CVE-2024-0003", "severity": "informational" },
+            { "cve_id": "CVE-2024-0004" }
+        ]))
+        .unwrap();
+
+        let severities: Vec<Severity> = vulnerabilities.iter().map(|v| v.severity).collect();
+        assert_eq!(
+            severities,
+            [
+                Severity::Unknown,
+                Severity::Low,
+                Severity::Unknown,
+                Severity::Unknown
+            ]
+        );
+    }
+
+    #[test]
+    fn deduplicate_merges_findings_across_scanners() {
+        let trivy = trivy::parse(&json!({
+            "Results": [{ "Vulnerabilities": [{
+                "VulnerabilityID": "CVE-2023-44487",
+                "PkgName": "golang.org/x/net",
+                "InstalledVersion": "0.15.0",
+                "FixedVersion": "0.17.0",
+                "Severity": "MEDIUM",
+                "PrimaryURL": "https://avd.aquasec.com/nvd/cve-2023-44487"
+            }]}]
+        }))
+        .unwrap();
+        let grype = grype::parse(&json!({
+            "matches": [
+                {
+                    "vulnerability": {
+                        "id": "cve-2023-44487",
+                        "severity": "High",
+                        "cvss": [{ "version": "3.1", "metrics": { "baseScore": 7.5 } }],
+                        "urls": ["https://nvd.nist.gov/vuln/detail/CVE-2023-44487"]
+                    },
+                    "artifact": {
+                        "name": "golang.org/x/net",
+                        "version": "0.15.0",
+                        "purl": "pkg:golang/golang.org/x/net@v0.15.0"
+                    }
+                },
+                {
+                    "vulnerability": { "id": "CVE-2023-44487", "severity": "High" },
+                    "artifact": { "name": "google.golang.org/grpc", "version": "1.58.2" }
+                }
+            ]
+        }))
+        .unwrap();
+        let mut vulnerabilities = trivy.vulnerabilities;
+        vulnerabilities.extend(grype.vulnerabilities);
+        vulnerabilities.push(finding(
+            "CVE-2024-0001",
+            Severity::Critical,
+            None,
+            "openssl",
+        ));
+        vulnerabilities.push(finding("CVE-2024-0002", Severity::High, Some(8.1), "zlib"));
+
+        let merged = deduplicate(vulnerabilities);
+
+        let ids: Vec<&str> = merged.iter().map(|v| v.cve_id.as_str()).collect();
+        assert_eq!(ids, ["CVE-2024-0001", "CVE-2024-0002", "CVE-2023-44487"]);
+        let rapid_reset = &merged[2];
+        assert_eq!(rapid_reset.severity, Severity::High);
+        assert_eq!(rapid_reset.cvss_score, Some(7.5));
+        assert_eq!(
+            rapid_reset.references,
+            [
+                "https://avd.aquasec.com/nvd/cve-2023-44487",
+                "https://nvd.nist.gov/vuln/detail/CVE-2023-44487"
+            ]
+        );
+        assert_eq!(
+            rapid_reset.packages,
+            [
+                AffectedPackage {
+                    name: "golang.org/x/net".to_string(),
+                    version: Some("0.15.0".to_string()),
+                    fixed_version: Some("0.17.0".to_string()),
+                    purl: Some("pkg:golang/golang.org/x/net@v0.15.0".to_string()),
+                },
+                AffectedPackage {
+                    name: "google.golang.org/grpc".to_string(),
+                    version: Some("1.58.2".to_string()),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn build_context_counts_the_merged_findings() {
+        let scan = SecurityScanData::default();
+        let mut duplicate = finding("CVE-2024-0001", Severity::High, None, "zlib");
+        duplicate.component = Some("openssl".to_string());
+        duplicate.packages.clear();
+        let vulnerabilities = deduplicate(vec![
+            finding("CVE-2024-0001", Severity::High, None, "openssl"),
+            duplicate,
+            finding("CVE-2024-0002", Severity::Low, None, "zlib"),
+            finding("CVE-2024-0003", Severity::Unknown, None, "tar"),
+        ]);
+        let metadata = serde_json::from_value(json!({
+            "title": "Security Scan Report",
+            "project_name": "QXProveIt Platform",
+            "version": "1.0.0",
+            "author": "Security Team",
+            "organization": "mcx Services, LLC"
+        }))
+        .unwrap();
+
+        let context = build_context(&scan, &vulnerabilities, &metadata);
+
+        assert_eq!(context["total_vulnerabilities"], 3);
+        assert_eq!(context["high_count"], 1);
+        assert_eq!(context["low_count"], 1);
+        assert_eq!(context["unknown_count"], 1);
+        assert_eq!(context["package_count"], 3);
+        assert_eq!(
+            context["executive_summary"],
+            "The scan found 3 unique vulnerabilities affecting 3 packages: 0 critical, 1 high, \
+             0 medium, 1 low and 1 unknown severity. 0 of them can be remediated by upgrading \
+             to a fixed version."
+        );
+    }
+
+    #[test]
+    fn sbom_context_matches_findings_by_purl_then_name() {
+        let sbom = cyclonedx::parse(&json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "components": [
+                { "name": "openssl", "version": "3.0.11", "purl": "pkg:deb/debian/openssl@3.0.11" },
+                { "name": "zlib", "version": "1.2.13" },
+                { "name": "tar", "version": "1.34" }
+            ]
+        }))
+        .unwrap();
+        let mut openssl = finding("CVE-2024-0001", Severity::Critical, None, "libssl3");
+        openssl.packages[0].purl = Some("pkg:deb/debian/openssl@3.0.11?arch=amd64".to_string());
+        let mut zlib = finding("CVE-2024-0002", Severity::High, None, "ZLIB");
+        zlib.packages[0].version = Some("1.2.13".to_string());
+        let vulnerabilities = vec![openssl, zlib];
+
+        let context = sbom_context(&[sbom], &vulnerabilities);
+
+        assert_eq!(context["component_count"], 3);
+        assert_eq!(context["vulnerable_count"], 2);
+        assert_eq!(context["clean_count"], 1);
+        let components = context["components"].as_array().unwrap();
+        assert_eq!(components[0]["name"], "openssl");
+        assert_eq!(
+            components[0]["vulnerabilities"][0]["cve_id"],
+            "CVE-2024-0001"
+        );
+        assert_eq!(
+            components[0]["vulnerabilities"][0]["anchor"],
+            "vulnerability-1"
+        );
+        assert_eq!(components[1]["name"], "tar");
+        assert_eq!(components[1]["vulnerabilities"], json!([]));
+        assert_eq!(
+            components[2]["vulnerabilities"][0]["cve_id"],
+            "CVE-2024-0002"
+        );
+    }
+}
@@ -0,0 +1,73 @@
+// document-generation-service/src/generators/template.rs
+
+use crate::error::Result;
+use crate::generators::normalize::normalize;
+use crate::generators::{Generator, TemplateRegistry};
+use crate::models::DocumentMetadata;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::info;
+
+/// Renders a manifest template directly against the request data, for
+/// document types that need no derived context.
+pub struct TemplateGenerator {
+    templates: Arc<TemplateRegistry>,
+    spec_type: String,
+}
+
+impl TemplateGenerator {
+    pub fn new(templates: Arc<TemplateRegistry>, spec_type: &str) -> Self {
+        Self {
+            templates,
+            spec_type: spec_type.to_string(),
+        }
+    }
+}
+
+/// Context a manifest template renders against: the request data, mapped
+/// from its documented shape onto the fields the template reads, both under
+/// `data` and merged into the top level, next to `metadata`.
+pub(crate) fn template_context(
+    spec_type: &str,
+    data: &Value,
+    metadata: &DocumentMetadata,
+) -> Value {
+    let data = normalize(spec_type, data);
+    let mut context = serde_json::json!({
+        "metadata": metadata,
+        "data": data,
+    });
+
+    if let (Value::Object(map), Value::Object(ctx_map)) = (&data, &mut context) {
+        for (key, value) in map {
+            ctx_map.insert(key.clone(), value.clone());
+        }
+    }
+    context
+}
+
+#[async_trait]
+impl Generator for TemplateGenerator {
+    async fn generate(&self, data: &Value, metadata: &DocumentMetadata) -> Result<String> {
+        let entry = self.templates.entry(&self.spec_type)?;
+
+        info!(
+            title = %metadata.title,
+            document = %entry.display_name,
+            "Generating document"
+        );
+
+        let context = template_context(&self.spec_type, data, metadata);
+        let rendered = self.templates.render(&self.spec_type, &context)?;
+
+        info!(
+            title = %metadata.title,
+            document = %entry.display_name,
+            size_bytes = rendered.len(),
+            "Document generated"
+        );
+
+        Ok(rendered)
+    }
+}
@@ -0,0 +1,443 @@
+// document-generation-service/src/generators/test_report.rs
+
+use crate::error::{DocumentError, Result};
+use crate::generators::{Generator, TemplateRegistry};
+use crate::ingest::junit;
+use crate::models::DocumentMetadata;
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tracing::info;
+
+/// Test run data. Either `suites` is given directly, or `junit_xml` carries one
+/// or more base64-encoded JUnit/xUnit reports that are parsed into suites.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TestExecutionData {
+    pub executive_summary: String,
+    pub test_run: TestRunInfo,
+    pub suites: Vec<TestSuite>,
+    pub junit_xml: Option<JunitInput>,
+    /// Requirement IDs verified by each test, keyed by test name or
+    /// `classname.name`; merged with links declared on the test cases.
+    pub requirement_links: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JunitInput {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TestRunInfo {
+    pub name: String,
+    pub environment: String,
+    pub build: String,
+    pub started_at: String,
+    pub executed_by: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TestSuite {
+    pub name: String,
+    pub test_cases: Vec<TestCase>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TestCase {
+    pub name: String,
+    pub classname: Option<String>,
+    pub status: TestStatus,
+    pub duration_seconds: f64,
+    pub message: Option<String>,
+    /// Additional attempts made before the final result.
+    pub retries: u32,
+    /// Passed only after one or more failed attempts.
+    pub flaky: bool,
+    pub requirements: Vec<String>,
+}
+
+impl TestCase {
+    fn qualified_name(&self) -> String {
+        match &self.classname {
+            Some(classname) if !classname.is_empty() => format!("{}.{}", classname, self.name),
+            _ => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", try_from = "String")]
+pub enum TestStatus {
+    #[default]
+    Passed,
+    Failed,
+    Skipped,
+    Error,
+}
+
+impl TryFrom<String> for TestStatus {
+    type Error = String;
+
+    fn try_from(value: String) -> std::result::Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "passed" | "pass" | "success" | "ok" => Ok(TestStatus::Passed),
+            "failed" | "fail" | "failure" => Ok(TestStatus::Failed),
+            "skipped" | "skip" | "ignored" | "disabled" | "pending" => Ok(TestStatus::Skipped),
+            "error" | "errored" | "broken" => Ok(TestStatus::Error),
+            _ => Err(format!("unknown test status '{}'", value)),
+        }
+    }
+}
+
+impl TestStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TestStatus::Passed => "Passed",
+            TestStatus::Failed => "Failed",
+            TestStatus::Skipped => "Skipped",
+            TestStatus::Error => "Error",
+        }
+    }
+}
+
+/// Result counts for a suite or the whole run.
+#[derive(Debug, Default, Serialize)]
+struct Totals {
+    total: usize,
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    errors: usize,
+    flaky: usize,
+    retries: u32,
+    duration_seconds: String,
+    pass_rate: String,
+    #[serde(skip)]
+    duration: f64,
+}
+
+impl Totals {
+    fn add(&mut self, case: &TestCase) {
+        self.total += 1;
+        match case.status {
+            TestStatus::Passed => self.passed += 1,
+            TestStatus::Failed => self.failed += 1,
+            TestStatus::Skipped => self.skipped += 1,
+            TestStatus::Error => self.errors += 1,
+        }
+        if case.flaky {
+            self.flaky += 1;
+        }
+        self.retries += case.retries;
+        self.duration += case.duration_seconds;
+    }
+
+    /// Pass rate over executed (non-skipped) tests.
+    fn finish(mut self) -> Self {
+        let executed = self.total - self.skipped;
+        let rate = if executed == 0 {
+            0.0
+        } else {
+            self.passed as f64 * 100.0 / executed as f64
+        };
+        self.pass_rate = format!("{:.1}", rate);
+        self.duration_seconds = format!("{:.2}", self.duration);
+        self
+    }
+}
+
+pub struct TestReportGenerator {
+    templates: Arc<TemplateRegistry>,
+}
+
+impl TestReportGenerator {
+    pub fn new(templates: Arc<TemplateRegistry>) -> Self {
+        Self { templates }
+    }
+}
+
+#[async_trait]
+impl Generator for TestReportGenerator {
+    async fn generate(&self, data: &Value, metadata: &DocumentMetadata) -> Result<String> {
+        info!(
+            title = %metadata.title,
+            "Generating Test Execution Report"
+        );
+
+        let mut run: TestExecutionData = serde_json::from_value(data.clone())
+            .map_err(|e| DocumentError::InvalidData(format!("test execution data: {}", e)))?;
+
+        if let Some(input) = run.junit_xml.take() {
+            let reports = match input {
+                JunitInput::Single(report) => vec![report],
+                JunitInput::Multiple(reports) => reports,
+            };
+            for report in reports {
+                let xml = general_purpose::STANDARD.decode(report.trim())?;
+                let xml = String::from_utf8(xml)
+                    .map_err(|e| DocumentError::InvalidData(format!("junit_xml: {}", e)))?;
+                run.suites.extend(junit::parse(&xml)?);
+            }
+        }
+
+        let context = build_context(&run, metadata);
+
+        let rendered = self.templates.render("test_execution_report", &context)?;
+
+        info!(
+            title = %metadata.title,
+            suites = run.suites.len(),
+            size_bytes = rendered.len(),
+            "Test execution report generated"
+        );
+
+        Ok(rendered)
+    }
+}
+
+/// Template context: run and per-suite totals, failures, flaky tests and the
+/// requirement verification matrix.
+fn build_context(run: &TestExecutionData, metadata: &DocumentMetadata) -> Value {
+    let mut overall = Totals::default();
+    let mut failures = Vec::new();
+    let mut flaky = Vec::new();
+    let mut coverage: BTreeMap<String, Vec<(String, TestStatus)>> = BTreeMap::new();
+
+    let suites: Vec<Value> = run
+        .suites
+        .iter()
+        .map(|suite| {
+            let mut totals = Totals::default();
+            let cases: Vec<Value> = suite
+                .test_cases
+                .iter()
+                .map(|case| {
+                    totals.add(case);
+                    overall.add(case);
+
+                    let qualified = case.qualified_name();
+                    let mut requirements = case.requirements.clone();
+                    for key in [&case.name, &qualified] {
+                        for requirement in run.requirement_links.get(key).into_iter().flatten() {
+                            if !requirements.contains(requirement) {
+                                requirements.push(requirement.clone());
+                            }
+                        }
+                    }
+                    for requirement in &requirements {
+                        coverage
+                            .entry(requirement.clone())
+                            .or_default()
+                            .push((qualified.clone(), case.status));
+                    }
+
+                    let mut entry = json!(case);
+                    entry["status_label"] = json!(case.status.label());
+                    entry["suite"] = json!(suite.name);
+                    entry["requirements"] = json!(requirements);
+                    if matches!(case.status, TestStatus::Failed | TestStatus::Error) {
+                        failures.push(entry.clone());
+                    }
+                    if case.flaky {
+                        flaky.push(entry.clone());
+                    }
+                    entry
+                })
+                .collect();
+
+            let mut row = json!(totals.finish());
+            row["name"] = json!(suite.name);
+            row["test_cases"] = json!(cases);
+            row
+        })
+        .collect();
+
+    let requirements: Vec<Value> = coverage
+        .into_iter()
+        .map(|(requirement, tests)| {
+            let status = if tests
+                .iter()
+                .any(|(_, s)| matches!(s, TestStatus::Failed | TestStatus::Error))
+            {
+                "Failed"
+            } else if tests.iter().all(|(_, s)| *s == TestStatus::Skipped) {
+                "Not Run"
+            } else if tests.iter().all(|(_, s)| *s == TestStatus::Passed) {
+                "Verified"
+            } else {
+                "Partially Verified"
+            };
+            json!({
+                "requirement_id": requirement,
+                "tests": tests.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+                "status": status,
+            })
+        })
+        .collect();
+
+    json!({
+        "metadata": metadata,
+        "executive_summary": run.executive_summary,
+        "test_run": run.test_run,
+        "summary": overall.finish(),
+        "suites": suites,
+        "failures": failures,
+        "flaky_tests": flaky,
+        "requirements": requirements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> DocumentMetadata {
+        serde_json::from_value(json!({
+            "title": "Release 2.4 Test Report",
+            "project_name": "Project",
+            "version": "2.4.0",
+            "author": "QA",
+            "organization": "Organization"
+        }))
+        .unwrap()
+    }
+
+    fn context(data: Value) -> Value {
+        let run: TestExecutionData = serde_json::from_value(data).unwrap();
+        build_context(&run, &metadata())
+    }
+
+    #[test]
+    fn build_context_totals_exclude_skipped_tests_from_the_pass_rate() {
+        let context = context(json!({
+            "suites": [
+                {
+                    "name": "api",
+                    "test_cases": [
+                        { "name": "login", "status": "passed", "duration_seconds": 1.25 },
+                        { "name": "logout", "status": "failed", "duration_seconds": 0.5, "message": "401" },
+                        { "name": "refresh", "status": "skipped" }
+                    ]
+                },
+                {
+                    "name": "ui",
+                    "test_cases": [
+                        { "name": "render", "status": "passed", "duration_seconds": 2.0,
+                          "retries": 2, "flaky": true },
+                        { "name": "resize", "status": "error", "retries": 1 }
+                    ]
+                }
+            ]
+        }));
+
+        let summary = &context["summary"];
+        assert_eq!(summary["total"], 5);
+        assert_eq!(summary["passed"], 2);
+        assert_eq!(summary["failed"], 1);
+        assert_eq!(summary["skipped"], 1);
+        assert_eq!(summary["errors"], 1);
+        assert_eq!(summary["pass_rate"], "50.0");
+        assert_eq!(summary["duration_seconds"], "3.75");
+        assert_eq!(summary["flaky"], 1);
+        assert_eq!(summary["retries"], 3);
+
+        let api = &context["suites"][0];
+        assert_eq!(api["total"], 3);
+        assert_eq!(api["pass_rate"], "50.0");
+        let ui = &context["suites"][1];
+        assert_eq!(ui["flaky"], 1);
+        assert_eq!(ui["retries"], 3);
+
+        let failures = context["failures"].as_array().unwrap();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0]["name"], "logout");
+        assert_eq!(failures[0]["suite"], "api");
+        assert_eq!(failures[1]["status_label"], "Error");
+        assert_eq!(context["flaky_tests"][0]["name"], "render");
+    }
+
+    #[test]
+    fn build_context_gives_an_all_skipped_run_a_zero_pass_rate() {
+        let context = context(json!({
+            "suites": [{ "name": "api", "test_cases": [{ "name": "login", "status": "skipped" }] }]
+        }));
+
+        assert_eq!(context["summary"]["pass_rate"], "0.0");
+    }
+
+    #[test]
+    fn build_context_merges_requirement_links_with_case_requirements() {
+        let context = context(json!({
+            "suites": [{
+                "name": "api",
+                "test_cases": [
+                    { "name": "login", "classname": "auth.Session", "requirements": ["REQ-1"] },
+                    { "name": "logout", "requirements": ["REQ-3"] }
+                ]
+            }],
+            "requirement_links": {
+                "login": ["REQ-1", "REQ-2"],
+                "auth.Session.login": ["REQ-4"],
+                "logout": ["REQ-3"]
+            }
+        }));
+
+        let login = &context["suites"][0]["test_cases"][0];
+        assert_eq!(login["requirements"], json!(["REQ-1", "REQ-2", "REQ-4"]));
+        let logout = &context["suites"][0]["test_cases"][1];
+        assert_eq!(logout["requirements"], json!(["REQ-3"]));
+        assert_eq!(
+            context["requirements"][0],
+            json!({ "requirement_id": "REQ-1", "tests": ["auth.Session.login"], "status": "Verified" })
+        );
+    }
+
+    #[test]
+    fn build_context_derives_each_requirement_status_from_its_tests() {
+        let context = context(json!({
+            "suites": [{
+                "name": "api",
+                "test_cases": [
+                    { "name": "a", "status": "passed", "requirements": ["REQ-FAILED", "REQ-PARTIAL", "REQ-VERIFIED"] },
+                    { "name": "b", "status": "failed", "requirements": ["REQ-FAILED"] },
+                    { "name": "c", "status": "skipped", "requirements": ["REQ-NOT-RUN", "REQ-PARTIAL"] },
+                    { "name": "d", "status": "skipped", "requirements": ["REQ-NOT-RUN"] },
+                    { "name": "e", "status": "error", "requirements": ["REQ-ERROR"] },
+                    { "name": "f", "status": "passed", "requirements": ["REQ-VERIFIED"] }
+                ]
+            }]
+        }));
+
+        let statuses: Vec<(&str, &str)> = context["requirements"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|row| {
+                (
+                    row["requirement_id"].as_str().unwrap(),
+                    row["status"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("REQ-ERROR", "Failed"),
+                ("REQ-FAILED", "Failed"),
+                ("REQ-NOT-RUN", "Not Run"),
+                ("REQ-PARTIAL", "Partially Verified"),
+                ("REQ-VERIFIED", "Verified"),
+            ]
+        );
+        assert_eq!(context["requirements"][2]["tests"], json!(["c", "d"]));
+    }
+}
@@ -0,0 +1,216 @@
+// document-generation-service/src/generators/tests.rs
+
+use super::{create_generator, TemplateRegistry};
+use crate::error::DocumentError;
+use crate::models::{DocumentMetadata, SpecificationType};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const README: &str = include_str!("../../README.md");
+
+/// Specification type for each heading under "Data Schema by Specification Type".
+const DATA_SCHEMA_SECTIONS: &[(&str, &str)] = &[
+    (
+        "ISO/IEC/IEEE 29148:2018 Software Requirements (SRS)",
+        "iso29148_software_requirements",
+    ),
+    (
+        "ISO/IEC/IEEE 29148:2018 Stakeholder Requirements (StakRS)",
+        "iso29148_stakeholder_requirements",
+    ),
+    (
+        "ISO/IEC/IEEE 29148:2018 Concept of Operations (ConOps)",
+        "iso29148_concept_of_operations",
+    ),
+    ("IEEE 830 Data Requirements Document (DRD)", "ieee830_drd"),
+    (
+        "MIL-STD-498 Software Requirements Specification (DI-IPSC-81433A)",
+        "milstd498_srs",
+    ),
+    ("Security Scan Report", "security_scan_report"),
+    ("Compliance Audit Report", "compliance_audit_report"),
+    ("Test Execution Report", "test_execution_report"),
+];
+
+fn templates() -> Arc<TemplateRegistry> {
+    let templates = TemplateRegistry::load("./templates").expect("templates compile");
+    templates.mark_empty_placeholders();
+    Arc::new(templates)
+}
+
+fn metadata() -> DocumentMetadata {
+    serde_json::from_value(json!({
+        "title": "Software Requirements Specification",
+        "project_name": "QXProveIt Platform",
+        "version": "1.0.0",
+        "author": "Engineering Team",
+        "organization": "mcx Services, LLC",
+        "classification": "Confidential",
+        "distribution_statement": "Internal Use Only",
+        "generated_date": "2026-02-05T18:00:00Z"
+    }))
+    .unwrap()
+}
+
+/// The body of a `## name` or `### name` section, up to the next heading of
+/// the same or a higher level.
+fn section<'a>(text: &'a str, heading: &str) -> &'a str {
+    let level = heading.split(' ').next().unwrap();
+    let start = text
+        .find(&format!("\n{}\n", heading))
+        .unwrap_or_else(|| panic!("README has no '{}' section", heading))
+        + heading.len()
+        + 2;
+    let rest = &text[start..];
+    let end = rest
+        .match_indices('\n')
+        .map(|(i, _)| i + 1)
+        .find(|&i| {
+            let hashes = rest[i..].chars().take_while(|c| *c == '#').count();
+            hashes > 0 && hashes <= level.len() && rest[i..].chars().nth(hashes) == Some(' ')
+        })
+        .unwrap_or(rest.len());
+    &rest[..end]
+}
+
+/// Every ```json block in `text`.
+fn json_blocks(text: &str) -> Vec<Value> {
+    text.split("```json\n")
+        .skip(1)
+        .map(|block| {
+            let body = &block[..block.find("```").unwrap()];
+            serde_json::from_str(body)
+                .unwrap_or_else(|e| panic!("README example is not valid JSON: {}\n{}", e, body))
+        })
+        .collect()
+}
+
+async fn render(templates: &Arc<TemplateRegistry>, spec_type: &str, data: &Value) -> String {
+    let violations = templates.validate(spec_type, data).unwrap();
+    assert!(
+        violations.is_empty(),
+        "{} README example fails its schema: {:?}",
+        spec_type,
+        violations
+    );
+
+    let spec: SpecificationType = serde_json::from_value(json!(spec_type)).unwrap();
+    create_generator(&spec, templates)
+        .unwrap()
+        .generate(data, &metadata())
+        .await
+        .unwrap_or_else(|e| panic!("{} README example failed to render: {}", spec_type, e))
+}
+
+fn assert_no_empty_placeholders(spec_type: &str, rendered: &str) {
+    let problems: Vec<&str> = rendered
+        .lines()
+        .filter(|line| {
+            line.contains("<<missing")
+                || line.contains("<<empty>>")
+                || line.contains("[object]")
+                || line.contains(", ]")
+        })
+        .collect();
+    assert!(
+        problems.is_empty(),
+        "{} renders empty placeholders:\n{}",
+        spec_type,
+        problems.join("\n")
+    );
+}
+
+#[tokio::test]
+async fn data_schema_examples_render_without_empty_placeholders() {
+    let templates = templates();
+    let schemas = section(README, "## Data Schema by Specification Type");
+
+    for (heading, spec_type) in DATA_SCHEMA_SECTIONS {
+        let examples = json_blocks(section(schemas, &format!("### {}", heading)));
+        assert!(!examples.is_empty(), "no example under '{}'", heading);
+
+        for data in &examples {
+            let rendered = render(&templates, spec_type, data).await;
+            assert_no_empty_placeholders(spec_type, &rendered);
+        }
+    }
+}
+
+#[tokio::test]
+async fn request_examples_render_without_empty_placeholders() {
+    let templates = templates();
+    let examples = json_blocks(section(README, "## Examples"));
+    assert!(!examples.is_empty());
+
+    for request in &examples {
+        let spec_type = request["specification_type"].as_str().unwrap();
+        let rendered = render(&templates, spec_type, &request["data"]).await;
+        assert_no_empty_placeholders(spec_type, &rendered);
+    }
+}
+
+#[tokio::test]
+async fn milstd498_example_builds_the_qualification_matrix_and_parent_links() {
+    let templates = templates();
+    let schemas = section(README, "## Data Schema by Specification Type");
+    let mut data = json_blocks(section(
+        schemas,
+        "### MIL-STD-498 Software Requirements Specification (DI-IPSC-81433A)",
+    ))
+    .remove(0);
+    // A second requirement sharing a parent, to check the inverted trace
+    data["capabilities"][0]["requirements"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({
+            "id": "SRS-002",
+            "statement": "The CSCI shall store the planned route.",
+            "qualification_method": "Demonstration, Inspection",
+            "system_requirements": ["SSS-010", "SSS-005"]
+        }));
+
+    let rendered = render(&templates, "milstd498_srs", &data).await;
+
+    for row in [
+        "| SRS-001 | 3.2.1 |  | X | X |  |  |",
+        "| SRS-002 | 3.2.1 | X |  |  | X |  |",
+        "| SRS-020 | 3.3.1 |  | X |  |  |  |",
+        "| SRS-100 | 3.7 |  |  |  | X |  |",
+        "| SRS-001 | SSS-010 |",
+        "| SRS-002 | SSS-010, SSS-005 |",
+    ] {
+        assert!(
+            rendered.lines().any(|line| line == row),
+            "missing row {}",
+            row
+        );
+    }
+
+    let system_to_csci = &rendered[rendered.find("### 5.2").unwrap()..];
+    let parents = system_to_csci.find("| SSS-005 | SRS-002 |").unwrap();
+    let shared = system_to_csci
+        .find("| SSS-010 | SRS-001, SRS-002 |")
+        .unwrap();
+    assert!(parents < shared);
+}
+
+#[test]
+fn manifest_required_fields_are_reported_once_as_missing() {
+    let templates = templates();
+
+    for data in [json!({}), json!({ "capabilities": null })] {
+        let violations = templates.validate("milstd498_srs", &data).unwrap();
+        let missing: Vec<&str> = violations
+            .iter()
+            .filter(|v| v.is_missing())
+            .map(|v| v.path.as_str())
+            .collect();
+        assert_eq!(missing, ["/capabilities"], "{:?}", violations);
+    }
+
+    let violations = templates.validate("ieee830_drd", &json!({})).unwrap();
+    assert!(matches!(
+        DocumentError::from_violations(&violations),
+        DocumentError::MissingField(fields) if fields == "/data_items"
+    ));
+}
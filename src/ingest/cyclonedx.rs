@@ -0,0 +1,200 @@
+// document-generation-service/src/ingest/cyclonedx.rs
+
+use crate::error::{DocumentError, Result};
+use crate::generators::security_report::{Component, Sbom};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Bom {
+    spec_version: Option<String>,
+    metadata: Option<Metadata>,
+    components: Vec<BomComponent>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Metadata {
+    component: Option<BomComponent>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct BomComponent {
+    name: String,
+    group: Option<String>,
+    version: Option<String>,
+    purl: Option<String>,
+    supplier: Option<Organization>,
+    publisher: Option<String>,
+    author: Option<String>,
+    licenses: Vec<LicenseChoice>,
+    components: Vec<BomComponent>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Organization {
+    name: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct LicenseChoice {
+    license: Option<License>,
+    expression: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct License {
+    id: Option<String>,
+    name: Option<String>,
+}
+
+/// Parse a CycloneDX JSON SBOM. Nested components are listed alongside
+/// their parents.
+pub fn parse(document: &Value) -> Result<Sbom> {
+    let bom = Bom::deserialize(document)
+        .map_err(|e| DocumentError::InvalidData(format!("sbom: {}", e)))?;
+
+    let mut components = Vec::new();
+    flatten(bom.components, &mut components);
+
+    Ok(Sbom {
+        format: match bom.spec_version {
+            Some(version) => format!("CycloneDX {}", version),
+            None => "CycloneDX".to_string(),
+        },
+        subject: bom
+            .metadata
+            .and_then(|m| m.component)
+            .map(|c| match c.version {
+                Some(version) => format!("{} {}", c.name, version),
+                None => c.name,
+            }),
+        components,
+    })
+}
+
+fn flatten(bom_components: Vec<BomComponent>, components: &mut Vec<Component>) {
+    for c in bom_components {
+        let licenses: Vec<String> = c
+            .licenses
+            .into_iter()
+            .filter_map(|l| l.expression.or(l.license.and_then(|l| l.id.or(l.name))))
+            .collect();
+        components.push(Component {
+            name: match c.group {
+                Some(group) if !group.is_empty() => format!("{}/{}", group, c.name),
+                _ => c.name,
+            },
+            version: c.version,
+            license: (!licenses.is_empty()).then(|| licenses.join(", ")),
+            purl: c.purl,
+            supplier: c.supplier.and_then(|s| s.name).or(c.publisher).or(c.author),
+        });
+        flatten(c.components, components);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_flattens_components_with_licenses_and_purls() {
+        let bom = json!({
+            "bomFormat": "CycloneDX",
+            "specVersion": "1.5",
+            "metadata": {
+                "component": { "type": "container", "name": "registry.example.com/api", "version": "1.4.2" }
+            },
+            "components": [
+                {
+                    "type": "library",
+                    "group": "org.apache.logging.log4j",
+                    "name": "log4j-core",
+                    "version": "2.14.1",
+                    "purl": "pkg:maven/org.apache.logging.log4j/log4j-core@2.14.1",
+                    "supplier": { "name": "Apache Software Foundation" },
+                    "licenses": [{ "license": { "id": "Apache-2.0" } }],
+                    "components": [{
+                        "name": "log4j-api",
+                        "version": "2.14.1",
+                        "publisher": "Apache",
+                        "licenses": [{ "license": { "name": "Apache License 2.0" } }]
+                    }]
+                },
+                {
+                    "name": "openssl",
+                    "group": "",
+                    "version": "3.0.11",
+                    "author": "OpenSSL Project",
+                    "licenses": [
+                        { "expression": "Apache-2.0 OR OpenSSL" },
+                        { "license": { "id": "MIT" } }
+                    ]
+                },
+                { "name": "busybox" }
+            ]
+        });
+
+        let sbom = parse(&bom).unwrap();
+
+        assert_eq!(sbom.format, "CycloneDX 1.5");
+        assert_eq!(
+            sbom.subject.as_deref(),
+            Some("registry.example.com/api 1.4.2")
+        );
+        let names: Vec<&str> = sbom.components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "org.apache.logging.log4j/log4j-core",
+                "log4j-api",
+                "openssl",
+                "busybox"
+            ]
+        );
+
+        let log4j = &sbom.components[0];
+        assert_eq!(log4j.version.as_deref(), Some("2.14.1"));
+        assert_eq!(log4j.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(
+            log4j.purl.as_deref(),
+            Some("pkg:maven/org.apache.logging.log4j/log4j-core@2.14.1")
+        );
+        assert_eq!(
+            log4j.supplier.as_deref(),
+            Some("Apache Software Foundation")
+        );
+
+        let api = &sbom.components[1];
+        assert_eq!(api.license.as_deref(), Some("Apache License 2.0"));
+        assert_eq!(api.supplier.as_deref(), Some("Apache"));
+        assert_eq!(api.purl, None);
+
+        let openssl = &sbom.components[2];
+        assert_eq!(
+            openssl.license.as_deref(),
+            Some("Apache-2.0 OR OpenSSL, MIT")
+        );
+        assert_eq!(openssl.supplier.as_deref(), Some("OpenSSL Project"));
+
+        let busybox = &sbom.components[3];
+        assert_eq!(busybox.version, None);
+        assert_eq!(busybox.license, None);
+        assert_eq!(busybox.supplier, None);
+    }
+
+    #[test]
+    fn parse_rejects_components_of_the_wrong_shape() {
+        assert!(matches!(
+            parse(&json!({ "bomFormat": "CycloneDX", "components": { "name": "openssl" } })),
+            Err(DocumentError::InvalidData(_))
+        ));
+    }
+}
@@ -0,0 +1,259 @@
+// document-generation-service/src/ingest/grype.rs
+
+use super::dedup;
+use crate::error::{DocumentError, Result};
+use crate::generators::security_report::{AffectedPackage, ScanReport, Severity, Vulnerability};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Report {
+    matches: Vec<Match>,
+    source: Option<Source>,
+    descriptor: Option<Descriptor>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Source {
+    target: Value,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Descriptor {
+    version: Option<String>,
+    timestamp: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Match {
+    vulnerability: Advisory,
+    related_vulnerabilities: Vec<Advisory>,
+    artifact: Artifact,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Advisory {
+    id: String,
+    data_source: Option<String>,
+    severity: Option<String>,
+    description: Option<String>,
+    urls: Vec<String>,
+    cvss: Vec<Cvss>,
+    fix: Fix,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Cvss {
+    version: String,
+    metrics: Metrics,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Metrics {
+    base_score: Option<f64>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Fix {
+    versions: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Artifact {
+    name: String,
+    version: Option<String>,
+    purl: Option<String>,
+}
+
+/// Parse a Grype JSON report (`grype -o json`). Matches reported under a
+/// GHSA or distribution advisory are filed under the related CVE when there
+/// is one, so they merge with other scanners' findings.
+pub fn parse(report: &Value) -> Result<ScanReport> {
+    let report = Report::deserialize(report)
+        .map_err(|e| DocumentError::InvalidData(format!("grype_json: {}", e)))?;
+
+    let vulnerabilities = report
+        .matches
+        .into_iter()
+        .map(|m| {
+            let advisory = m.vulnerability;
+            let related = m
+                .related_vulnerabilities
+                .into_iter()
+                .find(|r| r.id.starts_with("CVE-"));
+            let cvss_score = best_score(&advisory.cvss)
+                .or_else(|| related.as_ref().and_then(|r| best_score(&r.cvss)));
+            let severity = advisory
+                .severity
+                .map(Severity::from)
+                .filter(|s| *s != Severity::Unknown)
+                .or_else(|| cvss_score.map(Severity::from_cvss))
+                .unwrap_or_default();
+
+            let mut references: Vec<String> = advisory.data_source.into_iter().collect();
+            references.extend(advisory.urls);
+            let (cve_id, description) = match related {
+                Some(related) if !advisory.id.starts_with("CVE-") => {
+                    references.extend(related.data_source);
+                    (related.id, advisory.description.or(related.description))
+                }
+                related => (
+                    advisory.id,
+                    advisory
+                        .description
+                        .or_else(|| related.and_then(|r| r.description)),
+                ),
+            };
+            let references = dedup(references);
+
+            Vulnerability {
+                cve_id,
+                severity,
+                cvss_score,
+                description,
+                references,
+                packages: vec![AffectedPackage {
+                    name: m.artifact.name,
+                    version: m.artifact.version,
+                    fixed_version: (!advisory.fix.versions.is_empty())
+                        .then(|| advisory.fix.versions.join(", ")),
+                    purl: m.artifact.purl,
+                }],
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let descriptor = report.descriptor.unwrap_or_default();
+    let target = report.source.and_then(|source| match source.target {
+        Value::String(target) => Some(target),
+        target => target
+            .get("userInput")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    });
+
+    Ok(ScanReport {
+        scanner: Some(match descriptor.version {
+            Some(version) => format!("Grype v{}", version.trim_start_matches('v')),
+            None => "Grype".to_string(),
+        }),
+        target,
+        scan_date: descriptor.timestamp,
+        vulnerabilities,
+    })
+}
+
+/// Highest CVSS v3 base score, falling back to any version.
+fn best_score(cvss: &[Cvss]) -> Option<f64> {
+    let max = |v3_only: bool| {
+        cvss.iter()
+            .filter(|c| !v3_only || c.version.starts_with('3'))
+            .filter_map(|c| c.metrics.base_score)
+            .max_by(f64::total_cmp)
+    };
+    max(true).or_else(|| max(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_files_advisories_under_their_cve() {
+        let report = json!({
+            "matches": [
+                {
+                    "vulnerability": {
+                        "id": "GHSA-qppj-fm5r-hxr3",
+                        "dataSource": "https://github.com/advisories/GHSA-qppj-fm5r-hxr3",
+                        "severity": "Medium",
+                        "urls": ["https://github.com/advisories/GHSA-qppj-fm5r-hxr3"],
+                        "cvss": [{ "version": "3.1", "metrics": { "baseScore": 5.3 } }],
+                        "fix": { "versions": ["1.59.0"], "state": "fixed" }
+                    },
+                    "relatedVulnerabilities": [{
+                        "id": "CVE-2023-44487",
+                        "dataSource": "https://nvd.nist.gov/vuln/detail/CVE-2023-44487",
+                        "description": "HTTP/2 rapid reset.",
+                        "cvss": [{ "version": "3.1", "metrics": { "baseScore": 7.5 } }]
+                    }],
+                    "artifact": {
+                        "name": "google.golang.org/grpc",
+                        "version": "1.58.2",
+                        "purl": "pkg:golang/google.golang.org/grpc@v1.58.2"
+                    }
+                },
+                {
+                    "vulnerability": {
+                        "id": "CVE-2024-0001",
+                        "severity": null,
+                        "cvss": [
+                            { "version": "2.0", "metrics": { "baseScore": 10.0 } },
+                            { "version": "3.1", "metrics": { "baseScore": 9.1 } }
+                        ],
+                        "fix": { "versions": [] }
+                    },
+                    "artifact": { "name": "openssl", "version": "3.0.11" }
+                },
+                {
+                    "vulnerability": { "id": "CVE-2024-0002", "severity": "Negligible" },
+                    "artifact": { "name": "tar", "version": "1.34" }
+                }
+            ],
+            "source": { "type": "image", "target": { "userInput": "api:1.4.2" } },
+            "descriptor": { "name": "grype", "version": "0.74.0", "timestamp": "2026-02-05T17:00:00Z" }
+        });
+
+        let report = parse(&report).unwrap();
+
+        assert_eq!(report.scanner.as_deref(), Some("Grype v0.74.0"));
+        assert_eq!(report.target.as_deref(), Some("api:1.4.2"));
+        assert_eq!(report.vulnerabilities.len(), 3);
+
+        let grpc = &report.vulnerabilities[0];
+        assert_eq!(grpc.cve_id, "CVE-2023-44487");
+        assert_eq!(grpc.severity, Severity::Medium);
+        assert_eq!(grpc.cvss_score, Some(5.3));
+        assert_eq!(grpc.description.as_deref(), Some("HTTP/2 rapid reset."));
+        assert_eq!(
+            grpc.references,
+            [
+                "https://github.com/advisories/GHSA-qppj-fm5r-hxr3",
+                "https://nvd.nist.gov/vuln/detail/CVE-2023-44487"
+            ]
+        );
+        assert_eq!(grpc.packages[0].fixed_version.as_deref(), Some("1.59.0"));
+        assert_eq!(
+            grpc.packages[0].purl.as_deref(),
+            Some("pkg:golang/google.golang.org/grpc@v1.58.2")
+        );
+
+        // A null severity is rated from the CVSS v3 score
+        let openssl = &report.vulnerabilities[1];
+        assert_eq!(openssl.cvss_score, Some(9.1));
+        assert_eq!(openssl.severity, Severity::Critical);
+        assert_eq!(openssl.packages[0].fixed_version, None);
+
+        assert_eq!(report.vulnerabilities[2].severity, Severity::Low);
+    }
+
+    #[test]
+    fn parse_reads_a_plain_string_target() {
+        let report = parse(&json!({ "source": { "target": "dir:." } })).unwrap();
+
+        assert_eq!(report.scanner.as_deref(), Some("Grype"));
+        assert_eq!(report.target.as_deref(), Some("dir:."));
+        assert!(report.vulnerabilities.is_empty());
+    }
+}
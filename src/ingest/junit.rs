@@ -0,0 +1,267 @@
+// document-generation-service/src/ingest/junit.rs
+
+use crate::error::{DocumentError, Result};
+use crate::generators::test_report::{TestCase, TestStatus, TestSuite};
+use roxmltree::{Document, Node};
+
+/// Testcase property names that declare the requirements a test verifies.
+const REQUIREMENT_PROPERTIES: &[&str] = &["requirement", "requirements", "verifies"];
+
+/// Parse a JUnit/xUnit XML report (`<testsuites>` or a bare `<testsuite>`).
+///
+/// Repeated executions of the same test within a suite are folded into one
+/// case: a test that eventually passed after failing is marked flaky, and
+/// Surefire's `flakyFailure`/`rerunFailure` elements are counted as retries.
+pub fn parse(xml: &str) -> Result<Vec<TestSuite>> {
+    let doc = Document::parse(xml)
+        .map_err(|e| DocumentError::InvalidData(format!("junit_xml: {}", e)))?;
+
+    let suites = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("testsuite"))
+        .filter(|n| n.children().any(|c| c.has_tag_name("testcase")))
+        .map(|suite| TestSuite {
+            name: suite.attribute("name").unwrap_or("unnamed").to_string(),
+            test_cases: fold_retries(
+                suite
+                    .children()
+                    .filter(|c| c.has_tag_name("testcase"))
+                    .map(test_case)
+                    .collect(),
+            ),
+        })
+        .collect();
+
+    Ok(suites)
+}
+
+fn test_case(node: Node) -> TestCase {
+    let child = |tag: &str| node.children().find(|c| c.has_tag_name(tag));
+    let count = |tags: &[&str]| {
+        node.children()
+            .filter(|c| tags.iter().any(|t| c.has_tag_name(*t)))
+            .count() as u32
+    };
+
+    let (status, outcome) = if let Some(failure) = child("failure") {
+        (TestStatus::Failed, Some(failure))
+    } else if let Some(error) = child("error") {
+        (TestStatus::Error, Some(error))
+    } else if let Some(skipped) = child("skipped") {
+        (TestStatus::Skipped, Some(skipped))
+    } else {
+        (TestStatus::Passed, None)
+    };
+
+    let message = outcome.and_then(|n| {
+        n.attribute("message")
+            .or_else(|| n.text())
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+    });
+
+    let flaky_attempts = count(&["flakyFailure", "flakyError"]);
+    let rerun_attempts = count(&["rerunFailure", "rerunError"]);
+
+    let requirements = node
+        .children()
+        .filter(|c| c.has_tag_name("properties"))
+        .flat_map(|p| p.children().filter(|c| c.has_tag_name("property")))
+        .filter(|p| {
+            p.attribute("name")
+                .is_some_and(|name| REQUIREMENT_PROPERTIES.contains(&name.to_lowercase().as_str()))
+        })
+        .filter_map(|p| p.attribute("value").or_else(|| p.text()))
+        .flat_map(|value| value.split([',', ' ']))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    TestCase {
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        classname: node.attribute("classname").map(str::to_string),
+        status,
+        duration_seconds: node.attribute("time").and_then(seconds).unwrap_or(0.0),
+        message,
+        retries: flaky_attempts + rerun_attempts,
+        flaky: status == TestStatus::Passed && flaky_attempts > 0,
+        requirements,
+    }
+}
+
+/// A `time` attribute in seconds. A lone comma is a decimal separator
+/// (`0,5`); alongside a point, or repeated, commas separate thousands.
+fn seconds(time: &str) -> Option<f64> {
+    let time = time.trim();
+    let time = if !time.contains('.') && time.matches(',').count() == 1 {
+        time.replace(',', ".")
+    } else {
+        time.replace(',', "")
+    };
+    time.parse().ok()
+}
+
+/// Merge repeated runs of the same `classname.name`, keeping the last result.
+fn fold_retries(cases: Vec<TestCase>) -> Vec<TestCase> {
+    let mut folded: Vec<TestCase> = Vec::with_capacity(cases.len());
+
+    for case in cases {
+        let existing = folded
+            .iter_mut()
+            .find(|c| c.name == case.name && c.classname == case.classname);
+        match existing {
+            Some(previous) => {
+                let failed_before = previous.flaky
+                    || matches!(previous.status, TestStatus::Failed | TestStatus::Error);
+                previous.retries += case.retries + 1;
+                previous.duration_seconds += case.duration_seconds;
+                previous.flaky = case.status == TestStatus::Passed && failed_before;
+                previous.status = case.status;
+                previous.message = case.message;
+                for requirement in case.requirements {
+                    if !previous.requirements.contains(&requirement) {
+                        previous.requirements.push(requirement);
+                    }
+                }
+            }
+            None => folded.push(case),
+        }
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(name: &str, status: TestStatus, requirements: &[&str]) -> TestCase {
+        TestCase {
+            name: name.to_string(),
+            classname: Some("auth.LoginTest".to_string()),
+            status,
+            duration_seconds: 1.0,
+            message: None,
+            retries: 0,
+            flaky: false,
+            requirements: requirements.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn parse_reads_outcomes_durations_and_requirements() {
+        let xml = r#"<testsuites>
+  <testsuite name="auth">
+    <testcase name="login" classname="auth.LoginTest" time="0,5">
+      <properties>
+        <property name="requirements" value="SRS-001, SRS-002"/>
+        <property name="Verifies">SRS-003</property>
+        <property name="owner" value="SRS-999"/>
+      </properties>
+    </testcase>
+    <testcase name="logout" classname="auth.LoginTest" time="1,234.5">
+      <error message="connection reset"/>
+    </testcase>
+    <testcase name="lockout" classname="auth.LoginTest" time="0.25">
+      <skipped>not supported on CI</skipped>
+    </testcase>
+    <testcase name="expiry" classname="auth.LoginTest">
+      <failure message="token still valid">stack trace</failure>
+    </testcase>
+  </testsuite>
+  <testsuite name="empty"/>
+</testsuites>"#;
+
+        let suites = parse(xml).unwrap();
+
+        assert_eq!(suites.len(), 1);
+        assert_eq!(suites[0].name, "auth");
+        let cases = &suites[0].test_cases;
+        assert_eq!(cases.len(), 4);
+
+        assert_eq!(cases[0].status, TestStatus::Passed);
+        assert_eq!(cases[0].duration_seconds, 0.5);
+        assert_eq!(cases[0].requirements, ["SRS-001", "SRS-002", "SRS-003"]);
+
+        assert_eq!(cases[1].status, TestStatus::Error);
+        assert_eq!(cases[1].duration_seconds, 1234.5);
+        assert_eq!(cases[1].message.as_deref(), Some("connection reset"));
+
+        assert_eq!(cases[2].status, TestStatus::Skipped);
+        assert_eq!(cases[2].duration_seconds, 0.25);
+        assert_eq!(cases[2].message.as_deref(), Some("not supported on CI"));
+
+        assert_eq!(cases[3].status, TestStatus::Failed);
+        assert_eq!(cases[3].duration_seconds, 0.0);
+        assert_eq!(cases[3].message.as_deref(), Some("token still valid"));
+    }
+
+    #[test]
+    fn parse_counts_surefire_flaky_and_rerun_failures() {
+        let xml = r#"<testsuite name="auth">
+  <testcase name="login" classname="auth.LoginTest" time="2">
+    <flakyFailure message="timeout"/>
+    <flakyError message="reset"/>
+  </testcase>
+  <testcase name="logout" classname="auth.LoginTest" time="3">
+    <failure message="still failing"/>
+    <rerunFailure message="first"/>
+  </testcase>
+</testsuite>"#;
+
+        let cases = &parse(xml).unwrap()[0].test_cases;
+
+        assert_eq!(cases[0].status, TestStatus::Passed);
+        assert_eq!(cases[0].retries, 2);
+        assert!(cases[0].flaky);
+
+        assert_eq!(cases[1].status, TestStatus::Failed);
+        assert_eq!(cases[1].retries, 1);
+        assert!(!cases[1].flaky);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_xml() {
+        assert!(matches!(
+            parse("<testsuite>"),
+            Err(DocumentError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn fold_retries_marks_a_pass_after_failure_flaky() {
+        let folded = fold_retries(vec![
+            case("login", TestStatus::Failed, &["SRS-001"]),
+            case("logout", TestStatus::Passed, &[]),
+            case("login", TestStatus::Error, &["SRS-002"]),
+            case("login", TestStatus::Passed, &["SRS-001"]),
+        ]);
+
+        assert_eq!(folded.len(), 2);
+        let login = &folded[0];
+        assert_eq!(login.status, TestStatus::Passed);
+        assert!(login.flaky);
+        assert_eq!(login.retries, 2);
+        assert_eq!(login.duration_seconds, 3.0);
+        assert_eq!(login.requirements, ["SRS-001", "SRS-002"]);
+        assert!(!folded[1].flaky);
+    }
+
+    #[test]
+    fn fold_retries_keeps_the_last_result() {
+        let mut passed = case("login", TestStatus::Passed, &[]);
+        passed.classname = Some("auth.OtherTest".to_string());
+        let mut failed = case("login", TestStatus::Failed, &[]);
+        failed.message = Some("still failing".to_string());
+
+        let folded = fold_retries(vec![case("login", TestStatus::Passed, &[]), passed, failed]);
+
+        assert_eq!(folded.len(), 2);
+        assert_eq!(folded[0].status, TestStatus::Failed);
+        assert!(!folded[0].flaky);
+        assert_eq!(folded[0].retries, 1);
+        assert_eq!(folded[0].message.as_deref(), Some("still failing"));
+        assert_eq!(folded[1].classname.as_deref(), Some("auth.OtherTest"));
+    }
+}
@@ -0,0 +1,23 @@
+// document-generation-service/src/ingest/mod.rs
+
+//! Parsers that turn third-party tool output into generator data.
+
+pub mod cyclonedx;
+pub mod grype;
+pub mod junit;
+pub mod oscal;
+pub mod requirements;
+pub mod sarif;
+pub mod spdx;
+pub mod trivy;
+
+/// Drops repeated values, keeping the first occurrence of each in order.
+pub(crate) fn dedup(values: Vec<String>) -> Vec<String> {
+    let mut unique: Vec<String> = Vec::new();
+    for value in values {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    unique
+}
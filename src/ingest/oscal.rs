@@ -0,0 +1,942 @@
+// document-generation-service/src/ingest/oscal.rs
+
+use super::dedup;
+use crate::error::{DocumentError, Result};
+use crate::generators::compliance_report::{
+    ComplianceAuditData, Control, ControlStatus, Evidence, Finding, Observation, PoamEntry,
+    PoamMilestone, Risk,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// Root keys of the OSCAL documents read here.
+const ASSESSMENT_RESULTS: &str = "assessment-results";
+const PLAN_OF_ACTION: &str = "plan-of-action-and-milestones";
+
+/// Risk characterization facets that give a finding's severity, in order of
+/// preference.
+const SEVERITY_FACETS: &[&str] = &["risk", "severity", "impact"];
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct AssessmentResults {
+    metadata: Metadata,
+    results: Vec<AssessmentResult>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct AssessmentResult {
+    description: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    reviewed_controls: ReviewedControls,
+    #[serde(flatten)]
+    items: Items,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ReviewedControls {
+    description: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct PlanOfAction {
+    metadata: Metadata,
+    poam_items: Vec<PoamItem>,
+    #[serde(flatten)]
+    items: Items,
+}
+
+/// The observations, risks and findings shared by both document types.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Items {
+    observations: Vec<OscalObservation>,
+    risks: Vec<OscalRisk>,
+    findings: Vec<OscalFinding>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct Metadata {
+    title: Option<String>,
+    parties: Vec<Party>,
+    responsible_parties: Vec<ResponsibleParty>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Party {
+    uuid: String,
+    name: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct ResponsibleParty {
+    role_id: String,
+    party_uuids: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct OscalObservation {
+    uuid: String,
+    title: Option<String>,
+    description: String,
+    methods: Vec<String>,
+    collected: Option<String>,
+    relevant_evidence: Vec<RelevantEvidence>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct RelevantEvidence {
+    href: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct OscalRisk {
+    uuid: String,
+    title: String,
+    description: String,
+    statement: Option<String>,
+    status: String,
+    characterizations: Vec<Characterization>,
+    deadline: Option<String>,
+    remediations: Vec<Remediation>,
+    related_observations: Vec<RelatedObservation>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Characterization {
+    facets: Vec<Facet>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Facet {
+    name: String,
+    value: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Remediation {
+    lifecycle: String,
+    title: String,
+    description: String,
+    tasks: Vec<Task>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Task {
+    title: String,
+    timing: Option<Timing>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct Timing {
+    on_date: Option<OnDate>,
+    within_date_range: Option<DateRange>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct OnDate {
+    date: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct DateRange {
+    end: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct OscalFinding {
+    uuid: String,
+    title: String,
+    description: String,
+    target: Option<Target>,
+    related_observations: Vec<RelatedObservation>,
+    related_risks: Vec<RelatedRisk>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct Target {
+    target_id: String,
+    title: Option<String>,
+    status: TargetStatus,
+    implementation_status: Option<TargetStatus>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct TargetStatus {
+    state: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct PoamItem {
+    uuid: String,
+    title: String,
+    props: Vec<Prop>,
+    related_findings: Vec<RelatedFinding>,
+    related_risks: Vec<RelatedRisk>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Prop {
+    name: String,
+    value: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct RelatedObservation {
+    observation_uuid: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct RelatedRisk {
+    risk_uuid: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+struct RelatedFinding {
+    finding_uuid: String,
+}
+
+/// Read the OSCAL `assessment-results` and `plan-of-action-and-milestones`
+/// documents in `data`, if any. Findings become control statuses (the worst
+/// across a control's objectives) with the related observations as
+/// evidence; unsatisfied findings are listed on their control, rated by the
+/// related risk. POA&M items take their schedule, status and milestones
+/// from their risks.
+pub fn parse(data: &Value) -> Result<Option<ComplianceAuditData>> {
+    let root = |key: &str| data.get(key).filter(|v| !v.is_null());
+    let assessment = root(ASSESSMENT_RESULTS)
+        .map(|v| {
+            AssessmentResults::deserialize(v)
+                .map_err(|e| DocumentError::InvalidData(format!("{}: {}", ASSESSMENT_RESULTS, e)))
+        })
+        .transpose()?;
+    let plan = root(PLAN_OF_ACTION)
+        .map(|v| {
+            PlanOfAction::deserialize(v)
+                .map_err(|e| DocumentError::InvalidData(format!("{}: {}", PLAN_OF_ACTION, e)))
+        })
+        .transpose()?;
+    if assessment.is_none() && plan.is_none() {
+        return Ok(None);
+    }
+
+    let mut audit = ComplianceAuditData::default();
+    let mut items = Items::default();
+    let mut poam_items = Vec::new();
+
+    if let Some(assessment) = assessment {
+        let info = &mut audit.audit;
+        info.auditor = assessors(&assessment.metadata).join(", ");
+        info.scope = assessment
+            .results
+            .iter()
+            .filter_map(|r| {
+                r.reviewed_controls
+                    .description
+                    .clone()
+                    .or(r.description.clone())
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        info.period_start = assessment
+            .results
+            .iter()
+            .filter_map(|r| r.start.as_deref().map(date))
+            .min()
+            .unwrap_or_default();
+        info.period_end = assessment
+            .results
+            .iter()
+            .filter_map(|r| r.end.as_deref().map(date))
+            .max()
+            .unwrap_or_default();
+        for result in assessment.results {
+            items.extend(result.items);
+        }
+    }
+    if let Some(plan) = plan {
+        if audit.audit.scope.is_empty() {
+            audit.audit.scope = plan.metadata.title.unwrap_or_default();
+        }
+        items.extend(plan.items);
+        poam_items = plan.poam_items;
+    }
+
+    let methods: BTreeSet<String> = items
+        .observations
+        .iter()
+        .flat_map(|o| o.methods.iter().map(|m| capitalize(m)))
+        .collect();
+    if !methods.is_empty() {
+        audit.audit.methodology = format!(
+            "Controls were assessed with the NIST SP 800-53A methods: {}.",
+            methods.into_iter().collect::<Vec<_>>().join(", ")
+        );
+    }
+
+    Ok(Some(Assessment::new(items).into_audit(audit, poam_items)))
+}
+
+/// Observations, risks and findings cross-referenced by UUID, with the
+/// report identifiers given to them.
+struct Assessment {
+    items: Items,
+    observation_ids: HashMap<String, String>,
+    risk_ids: HashMap<String, String>,
+    /// Controls of each finding, keyed by finding UUID.
+    finding_controls: HashMap<String, String>,
+    /// Controls of each risk, keyed by risk UUID.
+    risk_controls: HashMap<String, Vec<String>>,
+}
+
+impl Assessment {
+    fn new(items: Items) -> Self {
+        let number = |uuids: Vec<&String>, prefix: &str| -> HashMap<String, String> {
+            uuids
+                .into_iter()
+                .enumerate()
+                .map(|(i, uuid)| (uuid.clone(), format!("{}-{}", prefix, i + 1)))
+                .collect()
+        };
+        let observation_ids = number(items.observations.iter().map(|o| &o.uuid).collect(), "OBS");
+        let risk_ids = number(items.risks.iter().map(|r| &r.uuid).collect(), "R");
+
+        let mut finding_controls = HashMap::new();
+        let mut risk_controls: HashMap<String, Vec<String>> = HashMap::new();
+        for finding in &items.findings {
+            let Some(target) = &finding.target else {
+                continue;
+            };
+            let control = control_id(&target.target_id);
+            for risk in &finding.related_risks {
+                let controls = risk_controls.entry(risk.risk_uuid.clone()).or_default();
+                if !controls.contains(&control) {
+                    controls.push(control.clone());
+                }
+            }
+            finding_controls.insert(finding.uuid.clone(), control);
+        }
+
+        Self {
+            items,
+            observation_ids,
+            risk_ids,
+            finding_controls,
+            risk_controls,
+        }
+    }
+
+    fn into_audit(
+        self,
+        mut audit: ComplianceAuditData,
+        poam_items: Vec<PoamItem>,
+    ) -> ComplianceAuditData {
+        let mut statuses: Vec<Vec<ControlStatus>> = Vec::new();
+        let mut finding_number = 0;
+        for finding in &self.items.findings {
+            let Some(target) = &finding.target else {
+                continue;
+            };
+            let id = control_id(&target.target_id);
+            let index = match audit.controls.iter().position(|c| c.id == id) {
+                Some(index) => index,
+                None => {
+                    let title = target.title.as_deref().unwrap_or(&finding.title);
+                    audit.controls.push(Control {
+                        title: strip_control_id(title, &id),
+                        id,
+                        ..Default::default()
+                    });
+                    statuses.push(Vec::new());
+                    audit.controls.len() - 1
+                }
+            };
+            let control = &mut audit.controls[index];
+
+            let status = finding_status(target);
+            statuses[index].push(status);
+            if matches!(
+                status,
+                ControlStatus::Implemented | ControlStatus::NotApplicable
+            ) {
+                if !finding.description.trim().is_empty() {
+                    if !control.implementation_description.is_empty() {
+                        control.implementation_description.push_str("\n\n");
+                    }
+                    control
+                        .implementation_description
+                        .push_str(finding.description.trim());
+                }
+            } else {
+                finding_number += 1;
+                let risks: Vec<&OscalRisk> = finding
+                    .related_risks
+                    .iter()
+                    .filter_map(|r| self.risk(&r.risk_uuid))
+                    .collect();
+                control.findings.push(Finding {
+                    id: format!("F-{}", finding_number),
+                    severity: risks.iter().find_map(|r| severity(r)).unwrap_or_default(),
+                    description: if finding.description.trim().is_empty() {
+                        finding.title.clone()
+                    } else {
+                        finding.description.trim().to_string()
+                    },
+                    recommendation: risks.iter().find_map(|r| remediation(r)),
+                });
+            }
+
+            for related in &finding.related_observations {
+                self.add_evidence(control, &related.observation_uuid);
+            }
+        }
+        for (control, statuses) in audit.controls.iter_mut().zip(&statuses) {
+            control.status = combine(statuses);
+        }
+
+        audit.risks = self
+            .items
+            .risks
+            .iter()
+            .map(|risk| {
+                let facet = |name: &str| {
+                    facets(risk)
+                        .find(|f| f.name.eq_ignore_ascii_case(name))
+                        .map(|f| capitalize(&f.value))
+                };
+                Risk {
+                    id: self.risk_ids[&risk.uuid].clone(),
+                    title: risk.title.clone(),
+                    description: risk
+                        .statement
+                        .clone()
+                        .filter(|s| !s.trim().is_empty())
+                        .unwrap_or_else(|| risk.description.clone()),
+                    status: capitalize(&risk.status),
+                    likelihood: facet("likelihood"),
+                    impact: facet("impact"),
+                    deadline: risk.deadline.as_deref().map(date),
+                    remediation: remediation(risk),
+                    controls: self
+                        .risk_controls
+                        .get(&risk.uuid)
+                        .cloned()
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        audit.observations = self
+            .items
+            .observations
+            .iter()
+            .map(|observation| Observation {
+                id: self.observation_ids[&observation.uuid].clone(),
+                title: observation.title.clone().unwrap_or_else(|| {
+                    observation
+                        .description
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string()
+                }),
+                description: observation.description.clone(),
+                methods: observation.methods.iter().map(|m| capitalize(m)).collect(),
+                collected: observation.collected.as_deref().map(date),
+                controls: self.observation_controls(&observation.uuid),
+            })
+            .collect();
+
+        audit.poam = poam_items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| self.poam_entry(i, item))
+            .collect();
+
+        audit
+    }
+
+    fn risk(&self, uuid: &str) -> Option<&OscalRisk> {
+        self.items.risks.iter().find(|r| r.uuid == uuid)
+    }
+
+    /// The observation's evidence, or the observation itself when it cites
+    /// none, unless the control already lists it.
+    fn add_evidence(&self, control: &mut Control, observation_uuid: &str) {
+        let Some(id) = self.observation_ids.get(observation_uuid) else {
+            return;
+        };
+        if control.evidence.iter().any(|e| e.id.as_ref() == Some(id)) {
+            return;
+        }
+        let observation = self
+            .items
+            .observations
+            .iter()
+            .find(|o| o.uuid == observation_uuid)
+            .expect("numbered observations exist");
+        let summary = observation
+            .title
+            .clone()
+            .unwrap_or_else(|| observation.description.trim().to_string());
+
+        if observation.relevant_evidence.is_empty() {
+            control.evidence.push(Evidence {
+                id: Some(id.clone()),
+                description: summary.clone(),
+                location: None,
+            });
+        }
+        for evidence in &observation.relevant_evidence {
+            control.evidence.push(Evidence {
+                id: Some(id.clone()),
+                description: evidence
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| summary.clone()),
+                location: evidence.href.clone(),
+            });
+        }
+    }
+
+    /// Controls whose findings, or whose findings' risks, cite the
+    /// observation.
+    fn observation_controls(&self, observation_uuid: &str) -> Vec<String> {
+        let mut controls: Vec<String> = Vec::new();
+        let cites = |related: &[RelatedObservation]| {
+            related
+                .iter()
+                .any(|r| r.observation_uuid == observation_uuid)
+        };
+        for finding in &self.items.findings {
+            if cites(&finding.related_observations) {
+                controls.extend(self.finding_controls.get(&finding.uuid).cloned());
+            }
+        }
+        for risk in &self.items.risks {
+            if cites(&risk.related_observations) {
+                controls.extend(
+                    self.risk_controls
+                        .get(&risk.uuid)
+                        .into_iter()
+                        .flatten()
+                        .cloned(),
+                );
+            }
+        }
+        dedup(controls)
+    }
+
+    fn poam_entry(&self, index: usize, item: &PoamItem) -> PoamEntry {
+        let risks: Vec<&OscalRisk> = item
+            .related_risks
+            .iter()
+            .filter_map(|r| self.risk(&r.risk_uuid))
+            .collect();
+        let milestones: Vec<PoamMilestone> = risks
+            .iter()
+            .flat_map(|r| &r.remediations)
+            .flat_map(|r| &r.tasks)
+            .map(|task| PoamMilestone {
+                description: task.title.clone(),
+                due_date: task.timing.as_ref().and_then(due_date).unwrap_or_default(),
+            })
+            .collect();
+
+        let mut controls: Vec<String> = item
+            .related_findings
+            .iter()
+            .filter_map(|f| self.finding_controls.get(&f.finding_uuid).cloned())
+            .collect();
+        for risk in &risks {
+            controls.extend(
+                self.risk_controls
+                    .get(&risk.uuid)
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            );
+        }
+
+        PoamEntry {
+            id: item
+                .props
+                .iter()
+                .find(|p| p.name.eq_ignore_ascii_case("poam-id"))
+                .map(|p| p.value.clone())
+                .unwrap_or_else(|| format!("POAM-{}", index + 1)),
+            weakness: item.title.clone(),
+            scheduled_completion: risks
+                .iter()
+                .find_map(|r| r.deadline.as_deref().map(date))
+                .or_else(|| milestones.iter().map(|m| m.due_date.clone()).max())
+                .unwrap_or_default(),
+            status: risks
+                .first()
+                .map_or("Open".to_string(), |r| capitalize(&r.status)),
+            milestones,
+            controls: dedup(controls),
+        }
+    }
+}
+
+impl Items {
+    /// Add the items of a later result or document. The POA&M and the
+    /// assessment results usually carry the same risks and observations, so
+    /// an item whose UUID is already present replaces the earlier copy.
+    fn extend(&mut self, other: Items) {
+        merge(&mut self.observations, other.observations, |o| &o.uuid);
+        merge(&mut self.risks, other.risks, |r| &r.uuid);
+        merge(&mut self.findings, other.findings, |f| &f.uuid);
+    }
+}
+
+fn merge<T>(items: &mut Vec<T>, other: Vec<T>, uuid: fn(&T) -> &String) {
+    for item in other {
+        let existing = items
+            .iter()
+            .position(|i| !uuid(&item).is_empty() && uuid(i) == uuid(&item));
+        match existing {
+            Some(index) => items[index] = item,
+            None => items.push(item),
+        }
+    }
+}
+
+/// Names of the parties in an assessor role.
+fn assessors(metadata: &Metadata) -> Vec<String> {
+    metadata
+        .responsible_parties
+        .iter()
+        .filter(|r| r.role_id.contains("assess"))
+        .flat_map(|r| &r.party_uuids)
+        .filter_map(|uuid| metadata.parties.iter().find(|p| p.uuid == *uuid))
+        .filter_map(|p| p.name.clone())
+        .collect()
+}
+
+/// Control of an objective or statement ID: `ac-2.1_obj.a-1` -> `AC-2(1)`.
+fn control_id(target_id: &str) -> String {
+    let control = target_id.split('_').next().unwrap_or(target_id);
+    match control.split_once('.') {
+        Some((base, enhancement)) if base.contains('-') => {
+            format!("{}({})", base.to_uppercase(), enhancement)
+        }
+        _ => control.to_uppercase(),
+    }
+}
+
+/// `AC-2 Account Management` -> `Account Management` for control `AC-2`.
+fn strip_control_id(title: &str, id: &str) -> String {
+    let title = title.trim();
+    match title.get(..id.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(id) => {
+            let rest = title[id.len()..].trim_start_matches([' ', ':', '-']);
+            if rest.is_empty() {
+                title.to_string()
+            } else {
+                rest.to_string()
+            }
+        }
+        _ => title.to_string(),
+    }
+}
+
+/// Status of one objective: whether the assessment was satisfied, refined
+/// by the implementation status when it was not.
+fn finding_status(target: &Target) -> ControlStatus {
+    let implementation = target
+        .implementation_status
+        .as_ref()
+        .map(|s| s.state.as_str());
+    match (target.status.state.as_str(), implementation) {
+        (_, Some("not-applicable")) => ControlStatus::NotApplicable,
+        ("satisfied", _) => ControlStatus::Implemented,
+        (_, Some("partial")) => ControlStatus::PartiallyImplemented,
+        (_, Some("planned")) => ControlStatus::Planned,
+        _ => ControlStatus::NotImplemented,
+    }
+}
+
+/// Status of a control from its objectives: the common status when they
+/// agree, partially implemented when only some are met.
+fn combine(statuses: &[ControlStatus]) -> ControlStatus {
+    let applicable: Vec<ControlStatus> = statuses
+        .iter()
+        .copied()
+        .filter(|s| *s != ControlStatus::NotApplicable)
+        .collect();
+    let met = |s: &ControlStatus| {
+        matches!(
+            s,
+            ControlStatus::Implemented | ControlStatus::PartiallyImplemented
+        )
+    };
+    match applicable.first() {
+        None => ControlStatus::NotApplicable,
+        Some(first) if applicable.iter().all(|s| s == first) => *first,
+        _ if applicable.iter().any(met) => ControlStatus::PartiallyImplemented,
+        _ if applicable.contains(&ControlStatus::Planned) => ControlStatus::Planned,
+        _ => ControlStatus::NotImplemented,
+    }
+}
+
+fn facets(risk: &OscalRisk) -> impl Iterator<Item = &Facet> {
+    risk.characterizations.iter().flat_map(|c| &c.facets)
+}
+
+fn severity(risk: &OscalRisk) -> Option<String> {
+    SEVERITY_FACETS.iter().find_map(|name| {
+        facets(risk)
+            .find(|f| f.name.eq_ignore_ascii_case(name))
+            .map(|f| capitalize(&f.value))
+    })
+}
+
+/// The recommended or planned remediations of a risk.
+fn remediation(risk: &OscalRisk) -> Option<String> {
+    let steps: Vec<&str> = risk
+        .remediations
+        .iter()
+        .filter(|r| r.lifecycle != "completed")
+        .map(|r| {
+            if r.description.trim().is_empty() {
+                r.title.trim()
+            } else {
+                r.description.trim()
+            }
+        })
+        .filter(|s| !s.is_empty())
+        .collect();
+    (!steps.is_empty()).then(|| steps.join(" "))
+}
+
+fn due_date(timing: &Timing) -> Option<String> {
+    timing
+        .on_date
+        .as_ref()
+        .map(|d| date(&d.date))
+        .or_else(|| timing.within_date_range.as_ref().map(|r| date(&r.end)))
+}
+
+/// Date part of an OSCAL date-time: `2026-03-31T00:00:00Z` -> `2026-03-31`.
+fn date(value: &str) -> String {
+    value.split('T').next().unwrap_or(value).to_string()
+}
+
+/// `not-satisfied` -> `Not satisfied`, `EXAMINE` -> `Examine`.
+fn capitalize(value: &str) -> String {
+    let value = value.trim().replace('-', " ").to_lowercase();
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn target(state: &str, implementation: Option<&str>) -> Target {
+        Target {
+            target_id: "ac-2_obj.a".to_string(),
+            title: None,
+            status: TargetStatus {
+                state: state.to_string(),
+            },
+            implementation_status: implementation.map(|state| TargetStatus {
+                state: state.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn control_id_normalizes_objectives_and_enhancements() {
+        assert_eq!(control_id("ac-2"), "AC-2");
+        assert_eq!(control_id("ac-2_smt.a"), "AC-2");
+        assert_eq!(control_id("ac-2.1_obj.a-1"), "AC-2(1)");
+        assert_eq!(control_id("ia-5.12"), "IA-5(12)");
+        assert_eq!(
+            strip_control_id("AC-2 Account Management", "AC-2"),
+            "Account Management"
+        );
+        assert_eq!(
+            strip_control_id("ac-2: Account Management", "AC-2"),
+            "Account Management"
+        );
+        assert_eq!(strip_control_id("AC-2", "AC-2"), "AC-2");
+    }
+
+    #[test]
+    fn finding_status_prefers_not_applicable_then_satisfied() {
+        use ControlStatus::*;
+        assert_eq!(finding_status(&target("satisfied", None)), Implemented);
+        assert_eq!(
+            finding_status(&target("satisfied", Some("partial"))),
+            Implemented
+        );
+        assert_eq!(
+            finding_status(&target("satisfied", Some("not-applicable"))),
+            NotApplicable
+        );
+        assert_eq!(
+            finding_status(&target("not-satisfied", Some("partial"))),
+            PartiallyImplemented
+        );
+        assert_eq!(
+            finding_status(&target("not-satisfied", Some("planned"))),
+            Planned
+        );
+        assert_eq!(
+            finding_status(&target("not-satisfied", None)),
+            NotImplemented
+        );
+    }
+
+    #[test]
+    fn combine_takes_the_common_status_or_the_partial_one() {
+        use ControlStatus::*;
+        assert_eq!(combine(&[]), NotApplicable);
+        assert_eq!(combine(&[NotApplicable, NotApplicable]), NotApplicable);
+        assert_eq!(combine(&[Implemented, NotApplicable]), Implemented);
+        assert_eq!(
+            combine(&[Implemented, NotImplemented]),
+            PartiallyImplemented
+        );
+        assert_eq!(
+            combine(&[PartiallyImplemented, Planned]),
+            PartiallyImplemented
+        );
+        assert_eq!(combine(&[Planned, NotImplemented]), Planned);
+        assert_eq!(combine(&[NotImplemented, NotImplemented]), NotImplemented);
+    }
+
+    #[test]
+    fn parse_merges_items_that_share_a_uuid() {
+        let observation = |description: &str| {
+            json!({
+                "uuid": "obs-1",
+                "description": description,
+                "methods": ["EXAMINE"],
+                "collected": "2026-02-01T10:00:00Z"
+            })
+        };
+        let risk = json!({
+            "uuid": "risk-1",
+            "title": "Stale accounts",
+            "description": "Accounts are not disabled.",
+            "status": "open",
+            "characterizations": [{ "facets": [{ "name": "likelihood", "value": "high" }] }]
+        });
+        let data = json!({
+            "assessment-results": {
+                "results": [
+                    {
+                        "start": "2026-02-01T00:00:00Z",
+                        "end": "2026-02-10T00:00:00Z",
+                        "observations": [observation("First pass.")],
+                        "risks": [risk],
+                        "findings": [{
+                            "uuid": "finding-1",
+                            "title": "AC-2 Account Management",
+                            "description": "Inactive accounts remain enabled.",
+                            "target": { "target-id": "ac-2_obj.a", "status": { "state": "not-satisfied" } },
+                            "related-observations": [{ "observation-uuid": "obs-1" }],
+                            "related-risks": [{ "risk-uuid": "risk-1" }]
+                        }]
+                    },
+                    {
+                        "start": "2026-03-01T00:00:00Z",
+                        "end": "2026-03-05T00:00:00Z",
+                        "observations": [observation("Retest.")],
+                        "findings": [{
+                            "uuid": "finding-1",
+                            "title": "AC-2 Account Management",
+                            "description": "Inactive accounts are disabled.",
+                            "target": { "target-id": "ac-2_obj.a", "status": { "state": "satisfied" } },
+                            "related-observations": [{ "observation-uuid": "obs-1" }]
+                        }]
+                    }
+                ]
+            },
+            "plan-of-action-and-milestones": {
+                "risks": [risk],
+                "poam-items": [{
+                    "uuid": "poam-1",
+                    "title": "Disable stale accounts",
+                    "related-risks": [{ "risk-uuid": "risk-1" }]
+                }]
+            }
+        });
+
+        let audit = parse(&data).unwrap().unwrap();
+
+        assert_eq!(audit.audit.period_start, "2026-02-01");
+        assert_eq!(audit.audit.period_end, "2026-03-05");
+        assert_eq!(audit.controls.len(), 1);
+        let control = &audit.controls[0];
+        assert_eq!(control.id, "AC-2");
+        assert_eq!(control.title, "Account Management");
+        assert_eq!(control.status, ControlStatus::Implemented);
+        assert!(control.findings.is_empty());
+        assert_eq!(
+            control.implementation_description,
+            "Inactive accounts are disabled."
+        );
+        assert_eq!(control.evidence.len(), 1);
+
+        assert_eq!(audit.observations.len(), 1);
+        assert_eq!(audit.observations[0].description, "Retest.");
+        assert_eq!(audit.observations[0].controls, ["AC-2"]);
+        assert_eq!(audit.risks.len(), 1);
+        assert_eq!(audit.risks[0].likelihood.as_deref(), Some("High"));
+        assert_eq!(audit.poam.len(), 1);
+        assert_eq!(audit.poam[0].id, "POAM-1");
+        assert_eq!(audit.poam[0].status, "Open");
+    }
+
+    #[test]
+    fn parse_rejects_malformed_documents() {
+        assert!(parse(&json!({ "controls": [] })).unwrap().is_none());
+
+        for data in [
+            json!({ "assessment-results": { "results": "none" } }),
+            json!({ "assessment-results": { "results": [{ "findings": [{ "uuid": 7 }] }] } }),
+            json!({ "plan-of-action-and-milestones": [] }),
+        ] {
+            assert!(
+                matches!(parse(&data), Err(DocumentError::InvalidData(_))),
+                "{data}"
+            );
+        }
+    }
+}
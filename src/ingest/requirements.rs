@@ -0,0 +1,614 @@
+// document-generation-service/src/ingest/requirements.rs
+
+use crate::error::{DocumentError, Result};
+use crate::exports::requirements::{needs_formula_quote, Requirement};
+use crate::gcs::DocumentStorage;
+use crate::models::{ImportFormat, SpecificationImport};
+use base64::{engine::general_purpose, Engine as _};
+use roxmltree::{Document, Node};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// Attribute names (ReqIF long names or CSV headings, compared ignoring case)
+/// recognised for each requirement field. The first entries are the names
+/// the exports write; the rest are common DOORS and Polarion names.
+const ID_NAMES: &[&str] = &[
+    "ReqIF.ForeignID",
+    "ID",
+    "Identifier",
+    "Requirement ID",
+    "Absolute Number",
+];
+const TITLE_NAMES: &[&str] = &[
+    "ReqIF.Name",
+    "Title",
+    "Name",
+    "ReqIF.ChapterName",
+    "Object Heading",
+];
+const STATEMENT_NAMES: &[&str] = &[
+    "ReqIF.Text",
+    "Statement",
+    "Object Text",
+    "Text",
+    "Description",
+    "Requirement",
+];
+const TYPE_NAMES: &[&str] = &["Type", "Category", "Requirement Type", "ReqIF.Category"];
+const PRIORITY_NAMES: &[&str] = &["Priority"];
+const CRITICALITY_NAMES: &[&str] = &["Criticality"];
+const VERIFICATION_NAMES: &[&str] = &["Verification Method", "Verification"];
+const PARENTS_NAMES: &[&str] = &["Parents", "Parent", "Derived From"];
+const CHILDREN_NAMES: &[&str] = &["Children", "Child", "Derives To"];
+
+/// ReqIF attribute the export writes out-of-set links to, as `parent <id>`
+/// and `child <id>` entries separated by `;`.
+const EXTERNAL_LINKS_NAME: &str = "External Links";
+
+/// Request `data` with the requirements from `import` merged in, in the shape
+/// the generator for `spec_type` reads. Fields of `data` other than the
+/// requirements are kept, so the rest of the document can still be supplied.
+/// A `gcs_path` must lie under `{tenant_id}/imports/` in the bucket.
+pub async fn import(
+    spec_type: &str,
+    data: &Value,
+    import: &SpecificationImport,
+    tenant_id: Option<&str>,
+    storage: Option<&DocumentStorage>,
+) -> Result<Value> {
+    let content = match (&import.content_base64, &import.gcs_path) {
+        (Some(encoded), _) => general_purpose::STANDARD.decode(encoded.trim())?,
+        (None, Some(path)) => {
+            let storage = storage.ok_or_else(|| {
+                DocumentError::InvalidData(format!("import: no bucket access to read {}", path))
+            })?;
+            let tenant_id = tenant_id.ok_or_else(|| {
+                DocumentError::InvalidData(format!("import: {} needs a tenant_id", path))
+            })?;
+            let object = import_object(storage.bucket(), tenant_id, path)?;
+            downloaded(path, storage.download(&object).await)?
+        }
+        (None, None) => {
+            return Err(DocumentError::MissingField(
+                "import.content_base64 or import.gcs_path".to_string(),
+            ))
+        }
+    };
+
+    let requirements = match import.format {
+        ImportFormat::ReqIF => parse_reqif(&String::from_utf8_lossy(&content))?,
+        ImportFormat::CSV => parse_csv(&content)?,
+    };
+    into_data(spec_type, data, &requirements)
+}
+
+/// Object name of an import `gcs_path`, given as `gs://{bucket}/{path}` or as
+/// a path within `bucket`. Only objects under the tenant's `imports/` prefix
+/// are readable, so a request cannot pull in another tenant's files or the
+/// exports stored next to its documents.
+fn import_object(bucket: &str, tenant_id: &str, gcs_path: &str) -> Result<String> {
+    let refuse = || {
+        DocumentError::InvalidData(format!(
+            "import: {} is not under {}/imports/ in bucket {}",
+            gcs_path, tenant_id, bucket
+        ))
+    };
+    let object = match gcs_path.strip_prefix("gs://") {
+        Some(url) => match url.split_once('/') {
+            Some((name, object)) if name == bucket => object,
+            _ => return Err(refuse()),
+        },
+        None => gcs_path.trim_start_matches('/'),
+    };
+
+    if tenant_id.is_empty() || tenant_id.contains('/') {
+        return Err(refuse());
+    }
+    let name = object
+        .strip_prefix(tenant_id)
+        .and_then(|rest| rest.strip_prefix("/imports/"))
+        .ok_or_else(refuse)?;
+    if name
+        .split('/')
+        .any(|segment| matches!(segment, "" | "." | ".."))
+    {
+        return Err(refuse());
+    }
+    Ok(object.to_string())
+}
+
+/// Content of a downloaded import. A missing object is a problem with the
+/// request; other bucket faults are worth retrying, like any other IO error.
+fn downloaded(gcs_path: &str, result: anyhow::Result<Option<Vec<u8>>>) -> Result<Vec<u8>> {
+    match result {
+        Ok(Some(content)) => Ok(content),
+        Ok(None) => Err(DocumentError::InvalidData(format!(
+            "import: {} does not exist",
+            gcs_path
+        ))),
+        Err(e) => Err(std::io::Error::other(format!("import: {e:#}")).into()),
+    }
+}
+
+/// Requirements from a ReqIF exchange document. SPEC-OBJECTs without text
+/// (DOORS section headings) are skipped; each SPEC-RELATION links its source
+/// to its target as a parent.
+pub fn parse_reqif(xml: &str) -> Result<Vec<Requirement>> {
+    let doc =
+        Document::parse(xml).map_err(|e| DocumentError::InvalidData(format!("reqif: {}", e)))?;
+    let identified = |tag: &'static str| {
+        doc.descendants()
+            .filter(move |n| n.has_tag_name(tag))
+            .filter_map(|n| Some((n.attribute("IDENTIFIER")?, n)))
+    };
+
+    let attribute_names: HashMap<&str, &str> = doc
+        .descendants()
+        .filter(|n| n.tag_name().name().starts_with("ATTRIBUTE-DEFINITION-"))
+        .filter_map(|n| Some((n.attribute("IDENTIFIER")?, n.attribute("LONG-NAME")?)))
+        .collect();
+    let enum_values: HashMap<&str, &str> = identified("ENUM-VALUE")
+        .filter_map(|(id, n)| Some((id, n.attribute("LONG-NAME")?)))
+        .collect();
+
+    // Hierarchy order first, then any objects the specifications leave out
+    let mut order: Vec<&str> = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("SPEC-HIERARCHY"))
+        .filter_map(|n| child_text(n, "OBJECT"))
+        .collect();
+    for (id, _) in identified("SPEC-OBJECT") {
+        if !order.contains(&id) {
+            order.push(id);
+        }
+    }
+
+    let objects: HashMap<&str, Node> = identified("SPEC-OBJECT").collect();
+    let mut requirements = Vec::new();
+    let mut ids: HashMap<&str, String> = HashMap::new();
+    for object_id in order {
+        let Some(object) = objects.get(object_id) else {
+            continue;
+        };
+        let values: HashMap<String, String> = object
+            .descendants()
+            .filter(|n| n.tag_name().name().starts_with("ATTRIBUTE-VALUE-"))
+            .filter_map(|value| {
+                let definition = child_text(value, "DEFINITION")?;
+                let name = attribute_names.get(definition)?;
+                Some((name.to_lowercase(), attribute_value(value, &enum_values)?))
+            })
+            .collect();
+        let field = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| values.get(&name.to_lowercase()))
+                .cloned()
+        };
+
+        let Some(statement) = field(STATEMENT_NAMES) else {
+            continue;
+        };
+        let id = field(ID_NAMES).unwrap_or_else(|| object_id.to_string());
+        ids.insert(object_id, id.clone());
+
+        // Links the ReqIF export could not express as relations
+        let (mut parents, mut children) = (Vec::new(), Vec::new());
+        for link in field(&[EXTERNAL_LINKS_NAME])
+            .iter()
+            .flat_map(|l| l.split(';'))
+        {
+            match link.trim().split_once(' ') {
+                Some(("parent", id)) => parents.push(id.trim().to_string()),
+                Some(("child", id)) => children.push(id.trim().to_string()),
+                _ => {}
+            }
+        }
+
+        requirements.push(Requirement {
+            id,
+            title: field(TITLE_NAMES),
+            kind: field(TYPE_NAMES),
+            priority: field(PRIORITY_NAMES),
+            criticality: field(CRITICALITY_NAMES),
+            statement: Some(statement),
+            verification_method: field(VERIFICATION_NAMES),
+            parents,
+            children,
+        });
+    }
+
+    for relation in doc
+        .descendants()
+        .filter(|n| n.has_tag_name("SPEC-RELATION"))
+    {
+        let source = child_text(relation, "SOURCE").and_then(|id| ids.get(id));
+        let target = child_text(relation, "TARGET").and_then(|id| ids.get(id));
+        if let (Some(child), Some(parent)) = (source, target) {
+            if let Some(requirement) = requirements.iter_mut().find(|r| &r.id == child) {
+                requirement.parents.push(parent.clone());
+            }
+        }
+    }
+
+    if requirements.is_empty() {
+        return Err(DocumentError::InvalidData(
+            "reqif: no SPEC-OBJECT carries requirement text".to_string(),
+        ));
+    }
+    Ok(requirements)
+}
+
+/// Requirements from a CSV export with a heading row, such as the one
+/// `RequirementsCSV` produces. Columns are matched by heading; links in one
+/// cell are separated by `;` or `,`. Rows without an ID are skipped. A `'`
+/// the export would have written is removed, so a cell that genuinely began
+/// with `'` followed by a formula character loses its first apostrophe
+/// unless it came from the export; other leading apostrophes are kept.
+pub fn parse_csv(content: &[u8]) -> Result<Vec<Requirement>> {
+    let content = content.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(content);
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content);
+    let headings: Vec<String> = reader
+        .headers()
+        .map_err(csv_error)?
+        .iter()
+        .map(str::to_lowercase)
+        .collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| headings.iter().position(|h| *h == name.to_lowercase()))
+    };
+
+    let id_column = column(ID_NAMES)
+        .ok_or_else(|| DocumentError::InvalidData("requirements CSV: no ID column".to_string()))?;
+    let columns = [
+        column(TITLE_NAMES),
+        column(TYPE_NAMES),
+        column(PRIORITY_NAMES),
+        column(CRITICALITY_NAMES),
+        column(STATEMENT_NAMES),
+        column(VERIFICATION_NAMES),
+        column(PARENTS_NAMES),
+        column(CHILDREN_NAMES),
+    ];
+
+    let mut requirements = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .map(|value| match value.strip_prefix('\'') {
+                    // Quoted by the export so spreadsheets don't evaluate it
+                    Some(text) if needs_formula_quote(text) => text,
+                    _ => value,
+                })
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let Some(id) = cell(Some(id_column)) else {
+            continue;
+        };
+        let [title, kind, priority, criticality, statement, verification_method, parents, children] =
+            columns.map(cell);
+        requirements.push(Requirement {
+            id,
+            title,
+            kind,
+            priority,
+            criticality,
+            statement,
+            verification_method,
+            parents: split_links(parents.as_deref()),
+            children: split_links(children.as_deref()),
+        });
+    }
+
+    if requirements.is_empty() {
+        return Err(DocumentError::InvalidData(
+            "requirements CSV: no row has an ID".to_string(),
+        ));
+    }
+    Ok(requirements)
+}
+
+/// Merge `requirements` into `data` using the documented request shape for
+/// `spec_type`.
+pub fn into_data(spec_type: &str, data: &Value, requirements: &[Requirement]) -> Result<Value> {
+    let mut map = match data {
+        Value::Object(map) => map.clone(),
+        Value::Null => Map::new(),
+        _ => {
+            return Err(DocumentError::InvalidData(
+                "data must be an object to merge imported requirements".to_string(),
+            ))
+        }
+    };
+
+    match spec_type {
+        "iso29148_software_requirements" => {
+            let items = requirements
+                .iter()
+                .map(|r| {
+                    fields(&[
+                        ("id", Some(json!(r.id))),
+                        ("title", string(&r.title)),
+                        ("category", string(&r.kind)),
+                        ("priority", string(&r.priority)),
+                        ("criticality", string(&r.criticality)),
+                        (
+                            "description",
+                            Some(json!(r.statement.clone().unwrap_or_default())),
+                        ),
+                        ("verification_method", string(&r.verification_method)),
+                        ("parent_system_req", joined(&r.parents)),
+                    ])
+                })
+                .collect();
+            map.insert("requirements".to_string(), Value::Array(items));
+        }
+        "iso29148_system_requirements" => {
+            let items = requirements
+                .iter()
+                .map(|r| {
+                    fields(&[
+                        ("id", Some(json!(r.id))),
+                        ("title", string(&r.title)),
+                        ("type", string(&r.kind)),
+                        ("priority", string(&r.priority)),
+                        ("criticality", string(&r.criticality)),
+                        ("description", string(&r.statement)),
+                        ("verification_method", string(&r.verification_method)),
+                        ("parent_requirements", Some(json!(r.parents))),
+                        ("child_requirements", Some(json!(r.children))),
+                    ])
+                })
+                .collect();
+            map.insert("requirements".to_string(), Value::Array(items));
+        }
+        "iso29148_stakeholder_requirements" => {
+            let items = requirements
+                .iter()
+                .map(|r| {
+                    fields(&[
+                        ("id", Some(json!(r.id))),
+                        ("title", string(&r.title)),
+                        ("type", string(&r.kind)),
+                        ("priority", string(&r.priority)),
+                        ("criticality", string(&r.criticality)),
+                        (
+                            "requirement",
+                            Some(json!(r.statement.clone().unwrap_or_default())),
+                        ),
+                        (
+                            "verification",
+                            r.verification_method
+                                .as_ref()
+                                .map(|v| json!({ "method": v })),
+                        ),
+                        ("business_objective", joined(&r.parents)),
+                    ])
+                })
+                .collect();
+            // The imported list replaces any template-shaped one
+            map.remove("requirements");
+            map.insert("stakeholder_requirements".to_string(), Value::Array(items));
+            map.entry("stakeholders").or_insert_with(|| json!([]));
+        }
+        _ => {
+            return Err(DocumentError::InvalidData(format!(
+                "requirements import is not available for {}",
+                spec_type
+            )))
+        }
+    }
+
+    Ok(Value::Object(map))
+}
+
+/// Text of the `*-REF` element under the `parent` child of `node`.
+fn child_text<'a>(node: Node<'a, '_>, parent: &str) -> Option<&'a str> {
+    node.children()
+        .find(|c| c.has_tag_name(parent))?
+        .descendants()
+        .find(|c| c.tag_name().name().ends_with("-REF"))?
+        .text()
+        .map(str::trim)
+}
+
+/// The value of an ATTRIBUTE-VALUE-* element as text: THE-VALUE for simple
+/// types, the text content for XHTML and the value names for enumerations.
+fn attribute_value(value: Node, enum_values: &HashMap<&str, &str>) -> Option<String> {
+    let text = match value.tag_name().name() {
+        "ATTRIBUTE-VALUE-XHTML" => {
+            let content = value.children().find(|c| c.has_tag_name("THE-VALUE"))?;
+            let text: String = content
+                .descendants()
+                .filter(Node::is_text)
+                .filter_map(|n| n.text())
+                .collect();
+            text.split_whitespace().collect::<Vec<_>>().join(" ")
+        }
+        "ATTRIBUTE-VALUE-ENUMERATION" => value
+            .descendants()
+            .filter(|n| n.has_tag_name("ENUM-VALUE-REF"))
+            .filter_map(|n| n.text())
+            .filter_map(|id| enum_values.get(id.trim()).copied())
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => value.attribute("THE-VALUE")?.trim().to_string(),
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+fn split_links(cell: Option<&str>) -> Vec<String> {
+    cell.into_iter()
+        .flat_map(|links| links.split([';', ',']))
+        .map(str::trim)
+        .filter(|link| !link.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn string(value: &Option<String>) -> Option<Value> {
+    value.as_ref().map(|v| json!(v))
+}
+
+/// Links as one comma-separated field, for templates that print a single ID.
+fn joined(links: &[String]) -> Option<Value> {
+    (!links.is_empty()).then(|| json!(links.join(", ")))
+}
+
+/// An object with the fields that have values.
+fn fields(entries: &[(&str, Option<Value>)]) -> Value {
+    Value::Object(
+        entries
+            .iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value.clone()?)))
+            .collect(),
+    )
+}
+
+fn csv_error(e: csv::Error) -> DocumentError {
+    DocumentError::InvalidData(format!("requirements CSV: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_csv_matches_columns_by_heading() {
+        let csv = "\u{FEFF}Absolute Number,Object Heading,Object Text,Requirement Type,PRIORITY,Derived From,Derives To\n\
+                   SYS-001,Login,\"The system shall log in users, by password.\",Functional,High,\"STK-1; STK-2\",\n\
+                   ,Section heading,,,,,\n\
+                   SYS-002,,The system shall log out users.,,,\"SYS-001, STK-3\",TST-9\n";
+
+        let requirements = parse_csv(csv.as_bytes()).unwrap();
+
+        assert_eq!(requirements.len(), 2);
+        let login = &requirements[0];
+        assert_eq!(login.id, "SYS-001");
+        assert_eq!(login.title.as_deref(), Some("Login"));
+        assert_eq!(
+            login.statement.as_deref(),
+            Some("The system shall log in users, by password.")
+        );
+        assert_eq!(login.kind.as_deref(), Some("Functional"));
+        assert_eq!(login.priority.as_deref(), Some("High"));
+        assert_eq!(login.criticality, None);
+        assert_eq!(login.parents, ["STK-1", "STK-2"]);
+        assert!(login.children.is_empty());
+        let logout = &requirements[1];
+        assert_eq!(logout.title, None);
+        assert_eq!(logout.parents, ["SYS-001", "STK-3"]);
+        assert_eq!(logout.children, ["TST-9"]);
+    }
+
+    #[test]
+    fn parse_csv_rejects_files_without_requirements() {
+        for csv in [
+            "Title,Statement\nLogin,The system shall log in users.\n",
+            "ID,Title\n,Login\n",
+            "ID,Title\n",
+        ] {
+            assert!(matches!(
+                parse_csv(csv.as_bytes()),
+                Err(DocumentError::InvalidData(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn parse_csv_keeps_apostrophes_the_export_did_not_add() {
+        let csv = "ID,Title,Statement,Verification\n\
+                   SYS-001,'Tis a login,\"'=HYPERLINK(\"\"x\"\")\",''=Test\n\
+                   SYS-002,'',''hello,'\n";
+
+        let requirements = parse_csv(csv.as_bytes()).unwrap();
+
+        let login = &requirements[0];
+        assert_eq!(login.title.as_deref(), Some("'Tis a login"));
+        assert_eq!(login.statement.as_deref(), Some("=HYPERLINK(\"x\")"));
+        assert_eq!(login.verification_method.as_deref(), Some("'=Test"));
+        let quoted = &requirements[1];
+        assert_eq!(quoted.title.as_deref(), Some("''"));
+        assert_eq!(quoted.statement.as_deref(), Some("''hello"));
+        assert_eq!(quoted.verification_method.as_deref(), Some("'"));
+    }
+
+    #[tokio::test]
+    async fn import_merges_csv_requirements_into_data() {
+        let csv =
+            "ID,Title,Statement,Parents\nSYS-001,Login,The system shall log in users.,STK-1\n";
+        let file = SpecificationImport {
+            format: ImportFormat::CSV,
+            content_base64: Some(general_purpose::STANDARD.encode(csv)),
+            gcs_path: None,
+        };
+        let data = json!({ "introduction": { "purpose": "Purpose." } });
+
+        let data = import("iso29148_system_requirements", &data, &file, None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(data["introduction"]["purpose"], "Purpose.");
+        assert_eq!(data["requirements"][0]["id"], "SYS-001");
+    }
+
+    #[test]
+    fn import_object_accepts_only_the_tenant_imports_prefix() {
+        let tenant = "7b0c2a52-5d1e-4a8f-9c43-2f6d1e0a9b11";
+        for path in [
+            "gs://docs/7b0c2a52-5d1e-4a8f-9c43-2f6d1e0a9b11/imports/doors.reqif",
+            "7b0c2a52-5d1e-4a8f-9c43-2f6d1e0a9b11/imports/batch/doors.reqif",
+            "/7b0c2a52-5d1e-4a8f-9c43-2f6d1e0a9b11/imports/doors.reqif",
+        ] {
+            let object = import_object("docs", tenant, path).unwrap();
+            assert!(object.starts_with(&format!("{tenant}/imports/")), "{path}");
+        }
+
+        for path in [
+            // Another bucket
+            "gs://other/7b0c2a52-5d1e-4a8f-9c43-2f6d1e0a9b11/imports/doors.reqif",
+            // Another tenant's files, and this tenant's exports
+            "gs://docs/0e6f0d4c-1111-4f7a-8a2b-5c3d2e1f0a99/imports/doors.reqif",
+            "7b0c2a52-5d1e-4a8f-9c43-2f6d1e0a9b11/documents/1/2/requirements.csv",
+            "imports/doors.reqif",
+            "doors.reqif",
+            // Escapes and empty names
+            "7b0c2a52-5d1e-4a8f-9c43-2f6d1e0a9b11/imports/../documents/1/2/requirements.csv",
+            "7b0c2a52-5d1e-4a8f-9c43-2f6d1e0a9b11/imports/",
+            "7b0c2a52-5d1e-4a8f-9c43-2f6d1e0a9b11/imports//doors.reqif",
+        ] {
+            assert!(
+                matches!(
+                    import_object("docs", tenant, path),
+                    Err(DocumentError::InvalidData(_))
+                ),
+                "{path}"
+            );
+        }
+        assert!(import_object("docs", "", "/imports/doors.reqif").is_err());
+        assert!(import_object("docs", "a/b", "a/b/imports/doors.reqif").is_err());
+    }
+
+    #[test]
+    fn downloaded_retries_only_bucket_faults() {
+        assert_eq!(
+            downloaded("p", Ok(Some(b"ID\n".to_vec()))).unwrap(),
+            b"ID\n"
+        );
+
+        let missing = downloaded("p", Ok(None)).unwrap_err();
+        assert!(matches!(missing, DocumentError::InvalidData(_)));
+        assert!(!missing.is_retryable());
+
+        let fault = downloaded("p", Err(anyhow::anyhow!("connection reset"))).unwrap_err();
+        assert!(fault.is_retryable());
+    }
+}
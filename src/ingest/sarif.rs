@@ -0,0 +1,321 @@
+// document-generation-service/src/ingest/sarif.rs
+
+use crate::error::{DocumentError, Result};
+use crate::generators::security_report::{AffectedPackage, ScanReport, Severity, Vulnerability};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Log {
+    runs: Vec<Run>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+    invocations: Vec<Invocation>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Driver {
+    name: String,
+    version: Option<String>,
+    #[serde(rename = "semanticVersion")]
+    semantic_version: Option<String>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Rule {
+    id: String,
+    short_description: Option<Message>,
+    full_description: Option<Message>,
+    help_uri: Option<String>,
+    properties: Properties,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Properties {
+    /// CVSS score, written as a string by most tools and as a number by some.
+    #[serde(rename = "security-severity")]
+    security_severity: Option<Value>,
+    tags: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Message {
+    text: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: Option<String>,
+    rule_index: Option<usize>,
+    level: Option<String>,
+    message: Message,
+    locations: Vec<Value>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Invocation {
+    start_time_utc: Option<String>,
+}
+
+/// Parse a SARIF 2.1 log. Severity comes from the rule's
+/// `security-severity` score, then a severity tag, then the result level.
+/// Package details are read from Trivy-style messages (`Package:`,
+/// `Installed Version:`, `Fixed Version:` lines); otherwise the first
+/// location stands in for the package.
+pub fn parse(log: &Value) -> Result<ScanReport> {
+    let log =
+        Log::deserialize(log).map_err(|e| DocumentError::InvalidData(format!("sarif: {}", e)))?;
+
+    let mut report = ScanReport::default();
+    let mut scanners: Vec<String> = Vec::new();
+    for run in log.runs {
+        let driver = run.tool.driver;
+        let scanner = match driver.version.or(driver.semantic_version) {
+            Some(version) => format!("{} v{}", driver.name, version.trim_start_matches('v')),
+            None => driver.name,
+        };
+        if !scanner.is_empty() && !scanners.contains(&scanner) {
+            scanners.push(scanner);
+        }
+        if report.scan_date.is_none() {
+            report.scan_date = run
+                .invocations
+                .into_iter()
+                .find_map(|invocation| invocation.start_time_utc);
+        }
+
+        for result in run.results {
+            let rule = result
+                .rule_index
+                .and_then(|index| driver.rules.get(index))
+                .filter(|rule| result.rule_id.as_ref().is_none_or(|id| *id == rule.id))
+                .or_else(|| {
+                    let id = result.rule_id.as_ref()?;
+                    driver.rules.iter().find(|rule| rule.id == *id)
+                });
+            report
+                .vulnerabilities
+                .push(vulnerability(result, rule.unwrap_or(&Rule::default())));
+        }
+    }
+
+    report.scanner = (!scanners.is_empty()).then(|| scanners.join(", "));
+    Ok(report)
+}
+
+fn vulnerability(result: SarifResult, rule: &Rule) -> Vulnerability {
+    let message = result.message.text.unwrap_or_default();
+    let field = |name: &str| {
+        message.lines().find_map(|line| {
+            let value = line.trim().strip_prefix(name)?.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        })
+    };
+
+    let cvss_score = match &rule.properties.security_severity {
+        Some(Value::Number(score)) => score.as_f64(),
+        Some(Value::String(score)) => score.trim().parse::<f64>().ok(),
+        _ => None,
+    };
+    let severity = cvss_score
+        .map(Severity::from_cvss)
+        .or_else(|| {
+            rule.properties
+                .tags
+                .iter()
+                .map(|tag| Severity::from(tag.clone()))
+                .find(|s| *s != Severity::Unknown)
+        })
+        .unwrap_or(match result.level.as_deref() {
+            Some("error") => Severity::High,
+            Some("warning") => Severity::Medium,
+            Some("note") => Severity::Low,
+            _ => Severity::Unknown,
+        });
+
+    let package = field("Package:")
+        .map(|name| AffectedPackage {
+            name,
+            version: field("Installed Version:"),
+            fixed_version: field("Fixed Version:"),
+            ..Default::default()
+        })
+        .or_else(|| {
+            let uri = result
+                .locations
+                .first()?
+                .pointer("/physicalLocation/artifactLocation/uri")?;
+            Some(AffectedPackage {
+                name: uri.as_str()?.to_string(),
+                ..Default::default()
+            })
+        });
+
+    let text = |m: &Option<Message>| m.as_ref().and_then(|m| m.text.clone());
+    Vulnerability {
+        cve_id: result.rule_id.unwrap_or_else(|| rule.id.clone()),
+        title: text(&rule.short_description),
+        severity,
+        cvss_score,
+        description: text(&rule.full_description)
+            .or_else(|| (!message.is_empty()).then(|| message.clone())),
+        references: rule.help_uri.iter().cloned().collect(),
+        packages: package.into_iter().collect(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_rates_results_by_score_tag_and_level() {
+        let log = json!({
+            "version": "2.1.0",
+            "runs": [
+                {
+                    "tool": { "driver": {
+                        "name": "Trivy",
+                        "version": "0.48.0",
+                        "rules": [
+                            {
+                                "id": "CVE-2023-44487",
+                                "shortDescription": { "text": "HTTP/2 rapid reset" },
+                                "helpUri": "https://avd.aquasec.com/nvd/cve-2023-44487",
+                                "properties": { "security-severity": "7.5", "tags": ["vulnerability", "HIGH"] }
+                            },
+                            {
+                                "id": "CVE-2024-0001",
+                                "properties": { "tags": ["security", "CRITICAL"] }
+                            }
+                        ]
+                    }},
+                    "invocations": [{ "startTimeUtc": "2026-02-05T17:00:00Z" }],
+                    "results": [
+                        {
+                            "ruleId": "CVE-2023-44487",
+                            "ruleIndex": 0,
+                            "level": "error",
+                            "message": { "text": "Package: libnghttp2-14\nInstalled Version: 1.52.0-1\nFixed Version: 1.52.0-1+deb12u1" }
+                        },
+                        {
+                            "ruleId": "CVE-2024-0001",
+                            "ruleIndex": 0,
+                            "level": null,
+                            "message": { "text": "openssl is vulnerable" },
+                            "locations": [{ "physicalLocation": { "artifactLocation": { "uri": "usr/lib/libssl.so.3" } } }]
+                        }
+                    ]
+                },
+                {
+                    "tool": { "driver": { "name": "Semgrep", "semanticVersion": "1.50.0" } },
+                    "results": [
+                        { "ruleId": "python.lang.security.eval", "level": "warning", "message": { "text": "eval used" } },
+                        { "ruleId": "python.lang.security.debug", "message": { "text": "debug enabled" } }
+                    ]
+                }
+            ]
+        });
+
+        let report = parse(&log).unwrap();
+
+        assert_eq!(
+            report.scanner.as_deref(),
+            Some("Trivy v0.48.0, Semgrep v1.50.0")
+        );
+        assert_eq!(report.scan_date.as_deref(), Some("2026-02-05T17:00:00Z"));
+        let [rapid_reset, openssl, eval, debug] = report.vulnerabilities.as_slice() else {
+            panic!("expected four results");
+        };
+
+        assert_eq!(rapid_reset.cve_id, "CVE-2023-44487");
+        assert_eq!(rapid_reset.title.as_deref(), Some("HTTP/2 rapid reset"));
+        assert_eq!(rapid_reset.severity, Severity::High);
+        assert_eq!(rapid_reset.cvss_score, Some(7.5));
+        assert_eq!(
+            rapid_reset.references,
+            ["https://avd.aquasec.com/nvd/cve-2023-44487"]
+        );
+        assert_eq!(
+            rapid_reset.packages,
+            [AffectedPackage {
+                name: "libnghttp2-14".to_string(),
+                version: Some("1.52.0-1".to_string()),
+                fixed_version: Some("1.52.0-1+deb12u1".to_string()),
+                purl: None,
+            }]
+        );
+
+        // The rule index points at another rule, so the ID decides
+        assert_eq!(openssl.severity, Severity::Critical);
+        assert_eq!(openssl.cvss_score, None);
+        assert_eq!(openssl.packages[0].name, "usr/lib/libssl.so.3");
+        assert_eq!(
+            openssl.description.as_deref(),
+            Some("openssl is vulnerable")
+        );
+
+        assert_eq!(eval.severity, Severity::Medium);
+        assert_eq!(debug.severity, Severity::Unknown);
+        assert!(debug.packages.is_empty());
+    }
+
+    #[test]
+    fn parse_reads_numeric_security_severity_scores() {
+        let log = json!({
+            "version": "2.1.0",
+            "runs": [{
+                "tool": { "driver": {
+                    "name": "CodeQL",
+                    "rules": [
+                        { "id": "js/sql-injection", "properties": { "security-severity": 8.8 } },
+                        { "id": "js/log-injection", "properties": { "security-severity": 3 } }
+                    ]
+                }},
+                "results": [
+                    { "ruleId": "js/sql-injection", "message": { "text": "Query built from user input" } },
+                    { "ruleId": "js/log-injection", "message": { "text": "Log entry from user input" } }
+                ]
+            }]
+        });
+
+        let report = parse(&log).unwrap();
+
+        let [sql, log] = report.vulnerabilities.as_slice() else {
+            panic!("expected two results");
+        };
+        assert_eq!(sql.cvss_score, Some(8.8));
+        assert_eq!(sql.severity, Severity::High);
+        assert_eq!(log.cvss_score, Some(3.0));
+        assert_eq!(log.severity, Severity::Low);
+    }
+
+    #[test]
+    fn parse_rejects_logs_of_the_wrong_shape() {
+        assert!(matches!(
+            parse(&json!({ "runs": [{ "results": {} }] })),
+            Err(DocumentError::InvalidData(_))
+        ));
+    }
+}
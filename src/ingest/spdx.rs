@@ -0,0 +1,173 @@
+// document-generation-service/src/ingest/spdx.rs
+
+use crate::error::{DocumentError, Result};
+use crate::generators::security_report::{Component, Sbom};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// SPDX placeholders meaning the value is unknown or deliberately absent.
+const NO_VALUE: &[&str] = &["NOASSERTION", "NONE"];
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Document {
+    spdx_version: Option<String>,
+    name: Option<String>,
+    packages: Vec<Package>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Package {
+    name: String,
+    version_info: Option<String>,
+    supplier: Option<String>,
+    originator: Option<String>,
+    license_concluded: Option<String>,
+    license_declared: Option<String>,
+    external_refs: Vec<ExternalRef>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ExternalRef {
+    reference_type: String,
+    reference_locator: String,
+}
+
+/// Parse an SPDX 2.x JSON document. The concluded license is preferred over
+/// the declared one, and the package URL is taken from the `purl` external
+/// reference.
+pub fn parse(document: &Value) -> Result<Sbom> {
+    let document = Document::deserialize(document)
+        .map_err(|e| DocumentError::InvalidData(format!("sbom: {}", e)))?;
+
+    let components = document
+        .packages
+        .into_iter()
+        .map(|p| Component {
+            name: p.name,
+            version: known(p.version_info),
+            license: known(p.license_concluded).or_else(|| known(p.license_declared)),
+            purl: p
+                .external_refs
+                .into_iter()
+                .find(|r| r.reference_type == "purl")
+                .map(|r| r.reference_locator),
+            supplier: known(p.supplier)
+                .or_else(|| known(p.originator))
+                .map(|s| strip_actor_type(&s)),
+        })
+        .collect();
+
+    Ok(Sbom {
+        format: document
+            .spdx_version
+            .map_or("SPDX".to_string(), |v| v.replace('-', " ")),
+        subject: document.name,
+        components,
+    })
+}
+
+fn known(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty() && !NO_VALUE.contains(&v.trim()))
+}
+
+/// `Organization: Debian` → `Debian`.
+fn strip_actor_type(actor: &str) -> String {
+    ["Organization:", "Person:", "Tool:"]
+        .iter()
+        .find_map(|prefix| actor.strip_prefix(prefix))
+        .unwrap_or(actor)
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_reads_packages_with_licenses_and_purls() {
+        let document = json!({
+            "spdxVersion": "SPDX-2.3",
+            "SPDXID": "SPDXRef-DOCUMENT",
+            "name": "registry.example.com/api:1.4.2",
+            "packages": [
+                {
+                    "name": "libssl3",
+                    "SPDXID": "SPDXRef-Package-libssl3",
+                    "versionInfo": "3.0.11-1~deb12u2",
+                    "supplier": "Organization: Debian",
+                    "licenseConcluded": "Apache-2.0",
+                    "licenseDeclared": "OpenSSL",
+                    "externalRefs": [
+                        {
+                            "referenceCategory": "SECURITY",
+                            "referenceType": "cpe23Type",
+                            "referenceLocator": "cpe:2.3:a:openssl:openssl:3.0.11:*:*:*:*:*:*:*"
+                        },
+                        {
+                            "referenceCategory": "PACKAGE-MANAGER",
+                            "referenceType": "purl",
+                            "referenceLocator": "pkg:deb/debian/libssl3@3.0.11-1~deb12u2?arch=amd64"
+                        }
+                    ]
+                },
+                {
+                    "name": "semver",
+                    "versionInfo": "7.5.1",
+                    "supplier": "NOASSERTION",
+                    "originator": "Person: Isaac Z. Schlueter",
+                    "licenseConcluded": "NOASSERTION",
+                    "licenseDeclared": "ISC"
+                },
+                {
+                    "name": "base-files",
+                    "versionInfo": " ",
+                    "supplier": "NONE",
+                    "licenseConcluded": "NONE",
+                    "licenseDeclared": "NOASSERTION"
+                }
+            ]
+        });
+
+        let sbom = parse(&document).unwrap();
+
+        assert_eq!(sbom.format, "SPDX 2.3");
+        assert_eq!(
+            sbom.subject.as_deref(),
+            Some("registry.example.com/api:1.4.2")
+        );
+        assert_eq!(sbom.components.len(), 3);
+
+        let libssl = &sbom.components[0];
+        assert_eq!(libssl.name, "libssl3");
+        assert_eq!(libssl.version.as_deref(), Some("3.0.11-1~deb12u2"));
+        assert_eq!(libssl.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(
+            libssl.purl.as_deref(),
+            Some("pkg:deb/debian/libssl3@3.0.11-1~deb12u2?arch=amd64")
+        );
+        assert_eq!(libssl.supplier.as_deref(), Some("Debian"));
+
+        let semver = &sbom.components[1];
+        assert_eq!(semver.license.as_deref(), Some("ISC"));
+        assert_eq!(semver.supplier.as_deref(), Some("Isaac Z. Schlueter"));
+        assert_eq!(semver.purl, None);
+
+        let base_files = &sbom.components[2];
+        assert_eq!(base_files.version, None);
+        assert_eq!(base_files.license, None);
+        assert_eq!(base_files.supplier, None);
+    }
+
+    #[test]
+    fn parse_rejects_packages_of_the_wrong_shape() {
+        assert!(matches!(
+            parse(&json!({ "spdxVersion": "SPDX-2.3", "packages": [{ "name": 7 }] })),
+            Err(DocumentError::InvalidData(_))
+        ));
+    }
+}
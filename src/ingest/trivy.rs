@@ -0,0 +1,215 @@
+// document-generation-service/src/ingest/trivy.rs
+
+use super::dedup;
+use crate::error::{DocumentError, Result};
+use crate::generators::security_report::{AffectedPackage, ScanReport, Severity, Vulnerability};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// CVSS sources in order of preference.
+const CVSS_SOURCES: &[&str] = &["nvd", "ghsa", "redhat"];
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct Report {
+    created_at: Option<String>,
+    artifact_name: Option<String>,
+    trivy: Option<Tool>,
+    results: Vec<Target>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct Tool {
+    version: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct Target {
+    vulnerabilities: Vec<Finding>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct Finding {
+    #[serde(rename = "VulnerabilityID")]
+    vulnerability_id: String,
+    pkg_name: String,
+    pkg_identifier: Option<PkgIdentifier>,
+    installed_version: Option<String>,
+    fixed_version: Option<String>,
+    severity: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    #[serde(rename = "PrimaryURL")]
+    primary_url: Option<String>,
+    references: Vec<String>,
+    #[serde(rename = "CVSS")]
+    cvss: BTreeMap<String, Cvss>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct PkgIdentifier {
+    #[serde(rename = "PURL")]
+    purl: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct Cvss {
+    #[serde(rename = "V3Score")]
+    v3_score: Option<f64>,
+    #[serde(rename = "V2Score")]
+    v2_score: Option<f64>,
+}
+
+/// Parse a Trivy JSON report (`trivy image --format json`, schema version 2).
+pub fn parse(report: &Value) -> Result<ScanReport> {
+    let report = Report::deserialize(report)
+        .map_err(|e| DocumentError::InvalidData(format!("trivy_json: {}", e)))?;
+
+    let vulnerabilities = report
+        .results
+        .into_iter()
+        .flat_map(|target| target.vulnerabilities)
+        .map(|finding| {
+            let cvss_score = CVSS_SOURCES
+                .iter()
+                .filter_map(|source| finding.cvss.get(*source))
+                .chain(finding.cvss.values())
+                .find_map(|cvss| cvss.v3_score.or(cvss.v2_score));
+            let severity = finding
+                .severity
+                .map(Severity::from)
+                .filter(|s| *s != Severity::Unknown)
+                .or_else(|| cvss_score.map(Severity::from_cvss))
+                .unwrap_or_default();
+            let mut references: Vec<String> = finding.primary_url.into_iter().collect();
+            references.extend(finding.references);
+            let references = dedup(references);
+
+            Vulnerability {
+                cve_id: finding.vulnerability_id,
+                title: finding.title,
+                severity,
+                cvss_score,
+                description: finding.description,
+                references,
+                packages: vec![AffectedPackage {
+                    name: finding.pkg_name,
+                    version: finding.installed_version,
+                    fixed_version: finding.fixed_version.filter(|v| !v.is_empty()),
+                    purl: finding.pkg_identifier.and_then(|id| id.purl),
+                }],
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Ok(ScanReport {
+        scanner: Some(match report.trivy.and_then(|t| t.version) {
+            Some(version) => format!("Trivy v{}", version.trim_start_matches('v')),
+            None => "Trivy".to_string(),
+        }),
+        target: report.artifact_name,
+        scan_date: report.created_at,
+        vulnerabilities,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_reads_findings_across_targets() {
+        let report = json!({
+            "SchemaVersion": 2,
+            "CreatedAt": "2026-02-05T17:00:00Z",
+            "ArtifactName": "registry.example.com/api:1.4.2",
+            "Trivy": { "Version": "0.48.0" },
+            "Results": [
+                {
+                    "Target": "registry.example.com/api:1.4.2 (debian 12.4)",
+                    "Vulnerabilities": [{
+                        "VulnerabilityID": "CVE-2023-44487",
+                        "PkgName": "libnghttp2-14",
+                        "PkgIdentifier": { "PURL": "pkg:deb/debian/libnghttp2-14@1.52.0-1?arch=amd64" },
+                        "InstalledVersion": "1.52.0-1",
+                        "FixedVersion": "1.52.0-1+deb12u1",
+                        "Severity": "HIGH",
+                        "Title": "HTTP/2 rapid reset",
+                        "PrimaryURL": "https://avd.aquasec.com/nvd/cve-2023-44487",
+                        "References": [
+                            "https://nvd.nist.gov/vuln/detail/CVE-2023-44487",
+                            "https://avd.aquasec.com/nvd/cve-2023-44487",
+                            "https://nvd.nist.gov/vuln/detail/CVE-2023-44487"
+                        ],
+                        "CVSS": {
+                            "redhat": { "V3Score": 7.0 },
+                            "nvd": { "V2Score": 5.0, "V3Score": 7.5 }
+                        }
+                    }]
+                },
+                {
+                    "Target": "app/package-lock.json",
+                    "Vulnerabilities": [{
+                        "VulnerabilityID": "GHSA-c2qf-rxjj-qqgw",
+                        "PkgName": "semver",
+                        "InstalledVersion": "7.5.1",
+                        "FixedVersion": "",
+                        "Severity": null,
+                        "CVSS": { "ghsa": { "V3Score": 5.3 } }
+                    }]
+                },
+                { "Target": "app/requirements.txt" }
+            ]
+        });
+
+        let report = parse(&report).unwrap();
+
+        assert_eq!(report.scanner.as_deref(), Some("Trivy v0.48.0"));
+        assert_eq!(
+            report.target.as_deref(),
+            Some("registry.example.com/api:1.4.2")
+        );
+        assert_eq!(report.scan_date.as_deref(), Some("2026-02-05T17:00:00Z"));
+        assert_eq!(report.vulnerabilities.len(), 2);
+
+        let rapid_reset = &report.vulnerabilities[0];
+        assert_eq!(rapid_reset.cve_id, "CVE-2023-44487");
+        assert_eq!(rapid_reset.severity, Severity::High);
+        assert_eq!(rapid_reset.cvss_score, Some(7.5));
+        assert_eq!(
+            rapid_reset.references,
+            [
+                "https://avd.aquasec.com/nvd/cve-2023-44487",
+                "https://nvd.nist.gov/vuln/detail/CVE-2023-44487"
+            ]
+        );
+        let package = &rapid_reset.packages[0];
+        assert_eq!(package.name, "libnghttp2-14");
+        assert_eq!(package.fixed_version.as_deref(), Some("1.52.0-1+deb12u1"));
+        assert_eq!(
+            package.purl.as_deref(),
+            Some("pkg:deb/debian/libnghttp2-14@1.52.0-1?arch=amd64")
+        );
+
+        // No severity: rated from the CVSS score instead
+        let semver = &report.vulnerabilities[1];
+        assert_eq!(semver.severity, Severity::Medium);
+        assert_eq!(semver.packages[0].fixed_version, None);
+    }
+
+    #[test]
+    fn parse_rejects_reports_of_the_wrong_shape() {
+        assert!(matches!(
+            parse(&json!({ "Results": { "Target": "image" } })),
+            Err(DocumentError::InvalidData(_))
+        ));
+    }
+}
@@ -2,17 +2,31 @@
 
 mod config;
 mod error;
+mod exports;
+mod gcs;
 mod generators;
+mod ingest;
+mod marking;
 mod models;
+mod persistence;
+mod pipeline;
 mod pubsub;
 mod renderers;
 
-use crate::config::Config;
-use crate::pubsub::{MessageHandler, Publisher};
+use crate::config::{Config, ProcessingMode, PubSubConfig};
+use crate::gcs::DocumentStorage;
+use crate::generators::TemplateRegistry;
+use crate::persistence::DocumentDb;
+use crate::pipeline::DocumentPipeline;
+use crate::pubsub::{DeliveryTracker, MessageHandler, Publisher};
 use google_cloud_pubsub::client::{Client, ClientConfig};
-use google_cloud_pubsub::subscription::Subscription;
+use google_cloud_pubsub::subscriber::{ReceivedMessage, SubscriberConfig};
+use google_cloud_pubsub::subscription::{ReceiveConfig, Subscription};
+use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
-use tracing::{error, info};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -92,31 +106,99 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     )
     .await?;
 
+    // Initialize publisher for requests that exhaust their retries
+    let dead_letter = Publisher::new(
+        &config.pubsub.project_id,
+        &config.pubsub.dead_letter_topic,
+    )
+    .await?;
+
+    // Shared across all renderers so Pandoc subprocesses stay bounded per pod
+    let render_permits = Arc::new(Semaphore::new(config.render.max_concurrent_renders.max(1)));
+
+    // Document types from the manifest in templates.path, compiled once and shared
+    let templates = Arc::new(TemplateRegistry::load(&config.templates.path).map_err(|e| {
+        error!(path = %config.templates.path, "Failed to compile templates: {}", e);
+        e
+    })?);
+    // Held for the life of the service; dropping it stops the reloads
+    let _template_watcher = if config.templates.hot_reload {
+        Some(templates.watch()?)
+    } else {
+        None
+    };
+
     // Initialize message handler
-    let handler = Arc::new(MessageHandler::new());
-    let publisher = Arc::new(publisher);
+    let handler = match config.service.processing_mode {
+        ProcessingMode::Inline => {
+            // Only needed for requirements imports given as bucket paths
+            let storage = match DocumentStorage::new(&config.storage.bucket).await {
+                Ok(storage) => Some(storage),
+                Err(e) => {
+                    warn!("Bucket unavailable, imports must be inline: {:#}", e);
+                    None
+                }
+            };
+            MessageHandler::new(templates, &config.render, render_permits, storage)
+        }
+        ProcessingMode::Pipeline => {
+            eprintln!("Initializing document pipeline...");
+            let pool = PgPoolOptions::new()
+                .max_connections(config.storage.database_max_connections)
+                .connect(&config.storage.database_url)
+                .await
+                .map_err(|e| {
+                    error!("Failed to connect to database: {}", e);
+                    e
+                })?;
+            let storage = DocumentStorage::new(&config.storage.bucket).await?;
 
-    info!("Starting message processing loop");
+            info!(bucket = %config.storage.bucket, "Document pipeline initialized");
 
-    // Start processing messages
-    process_messages(
-        subscription,
+            let pipeline = DocumentPipeline::new(
+                DocumentDb::new(pool),
+                storage,
+                templates.clone(),
+                &config.render,
+                render_permits.clone(),
+            );
+            MessageHandler::with_pipeline(pipeline, templates, &config.render, render_permits)
+        }
+    };
+    let context = Arc::new(MessageContext {
         handler,
         publisher,
-        config.pubsub.max_concurrent_messages,
-    )
-    .await;
+        dead_letter,
+        deliveries: DeliveryTracker::new(),
+        max_delivery_attempts: config.pubsub.max_delivery_attempts.max(1),
+    });
+
+    info!(
+        max_concurrent_messages = config.pubsub.max_concurrent_messages,
+        max_concurrent_renders = config.render.max_concurrent_renders,
+        "Concurrency limits configured"
+    );
+
+    // Start processing messages
+    process_messages(subscription, context, &config.pubsub).await;
 
     Ok(())
 }
 
+/// Everything a subscriber callback needs to process and settle a message.
+struct MessageContext {
+    handler: MessageHandler,
+    publisher: Publisher,
+    dead_letter: Publisher,
+    deliveries: DeliveryTracker,
+    max_delivery_attempts: usize,
+}
+
 async fn process_messages(
     subscription: Subscription,
-    handler: Arc<MessageHandler>,
-    publisher: Arc<Publisher>,
-    _max_concurrent: usize,
+    context: Arc<MessageContext>,
+    pubsub_config: &PubSubConfig,
 ) {
-    use tokio_util::sync::CancellationToken;
     use tokio::signal;
 
     let cancel = CancellationToken::new();
@@ -135,6 +217,10 @@ async fn process_messages(
         }
     });
 
+    let max_concurrent = pubsub_config.max_concurrent_messages.max(1);
+    let receive_config = receive_config(pubsub_config);
+    let message_permits = Arc::new(Semaphore::new(max_concurrent));
+
     info!("Starting message processing loop");
 
     loop {
@@ -143,50 +229,31 @@ async fn process_messages(
             break;
         }
 
-        let handler_clone = handler.clone();
-        let publisher_clone = publisher.clone();
+        let context_clone = context.clone();
+        let permits_clone = message_permits.clone();
 
         info!("Calling subscription.receive() to wait for messages...");
 
         let result = subscription
             .receive(
                 move |message, cancel| {
-                    let handler = handler_clone.clone();
-                    let publisher = publisher_clone.clone();
+                    let context = context_clone.clone();
+                    let permits = permits_clone.clone();
 
                     async move {
                         if cancel.is_cancelled() {
                             return;
                         }
 
-                        info!(
-                            message_id = %message.message.message_id,
-                            "Processing message"
-                        );
-
-                        // Process the message
-                        let response = handler.handle_message(&message.message.data).await;
-
-                        // Publish response
-                        publisher.publish_response(&response).await;
-
-                        // Acknowledge the message
-                        if let Err(e) = message.ack().await {
-                            error!(
-                                message_id = %message.message.message_id,
-                                error = %e,
-                                "Failed to acknowledge message"
-                            );
-                        } else {
-                            info!(
-                                message_id = %message.message.message_id,
-                                "Message processed and acknowledged"
-                            );
-                        }
+                        let Ok(_permit) = permits.acquire().await else {
+                            return;
+                        };
+
+                        process_message(&context, &message, &cancel).await;
                     }
                 },
                 cancel.clone(),
-                None,
+                Some(receive_config.clone()),
             )
             .await;
 
@@ -204,3 +271,170 @@ async fn process_messages(
 
     info!("Message processing loop exited");
 }
+
+/// Longest `error` attribute on a dead-lettered message; Pub/Sub rejects
+/// attribute values over 1024 bytes.
+const ERROR_ATTRIBUTE_MAX_BYTES: usize = 256;
+
+/// First line of `error`, cut to `ERROR_ATTRIBUTE_MAX_BYTES` on a character
+/// boundary.
+fn error_summary(error: &str) -> String {
+    let line = error.lines().next().unwrap_or_default().trim();
+    if line.len() <= ERROR_ATTRIBUTE_MAX_BYTES {
+        return line.to_string();
+    }
+    let mut end = ERROR_ATTRIBUTE_MAX_BYTES - '…'.len_utf8();
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &line[..end])
+}
+
+/// Process one message and settle it: ack on success or permanent failure,
+/// nack retryable failures until `max_delivery_attempts`, then dead-letter.
+async fn process_message(
+    context: &MessageContext,
+    message: &ReceivedMessage,
+    cancel: &CancellationToken,
+) {
+    let message_id = &message.message.message_id;
+    let attempt = context
+        .deliveries
+        .record(message_id, message.delivery_attempt());
+
+    info!(
+        message_id = %message_id,
+        delivery_attempt = attempt,
+        "Processing message"
+    );
+
+    // Process the message
+    let outcome = context
+        .handler
+        .handle_message(&message.message, cancel)
+        .await;
+
+    if let Some(e) = outcome.error.as_ref().filter(|e| e.is_retryable()) {
+        if attempt < context.max_delivery_attempts {
+            warn!(
+                message_id = %message_id,
+                delivery_attempt = attempt,
+                error = %e,
+                "Retryable failure, nacking message for redelivery"
+            );
+            nack(message).await;
+            return;
+        }
+
+        let error_response = e.to_error_response();
+        let mut attributes: std::collections::HashMap<String, String> = [
+            ("request_id", outcome.response.request_id.clone()),
+            ("original_message_id", message_id.clone()),
+            ("delivery_attempt", attempt.to_string()),
+            ("error", error_summary(&error_response.error)),
+            ("error_type", error_response.error_type.clone()),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+        if let Some(correlation_id) = &outcome.response.correlation_id {
+            attributes.insert("correlation_id".to_string(), correlation_id.clone());
+        }
+
+        if context
+            .dead_letter
+            .publish_dead_letter(&message.message.data, &error_response, attributes)
+            .await
+            .is_err()
+        {
+            nack(message).await;
+            return;
+        }
+        context.deliveries.forget(message_id);
+
+        warn!(
+            message_id = %message_id,
+            delivery_attempt = attempt,
+            error = %e,
+            "Retries exhausted, message dead-lettered"
+        );
+    }
+
+    // Publish response
+    if context
+        .publisher
+        .publish_response(&outcome.response)
+        .await
+        .is_err()
+    {
+        nack(message).await;
+        return;
+    }
+
+    // Acknowledge the message
+    if let Err(e) = message.ack().await {
+        error!(
+            message_id = %message_id,
+            error = %e,
+            "Failed to acknowledge message"
+        );
+    } else {
+        context.deliveries.forget(message_id);
+        info!(
+            message_id = %message_id,
+            "Message processed and acknowledged"
+        );
+    }
+}
+
+async fn nack(message: &ReceivedMessage) {
+    if let Err(e) = message.nack().await {
+        error!(
+            message_id = %message.message.message_id,
+            error = %e,
+            "Failed to nack message"
+        );
+    }
+}
+
+/// Subscriber settings derived from `PubSubConfig`: one worker per allowed
+/// in-flight message, and server-side flow control so the pod is never handed
+/// more messages than it can work on before their ack deadline.
+fn receive_config(config: &PubSubConfig) -> ReceiveConfig {
+    let max_concurrent = config.max_concurrent_messages.max(1);
+
+    ReceiveConfig {
+        worker_count: max_concurrent,
+        channel_capacity: Some(max_concurrent),
+        subscriber_config: Some(SubscriberConfig {
+            stream_ack_deadline_seconds: config.ack_deadline_seconds,
+            max_outstanding_messages: max_concurrent as i64,
+            max_outstanding_bytes: config.max_outstanding_bytes,
+            ..Default::default()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_summary_keeps_the_first_line_within_the_attribute_limit() {
+        assert_eq!(
+            error_summary("  Pandoc error: exit 43\nError producing PDF.\n! LaTeX Error"),
+            "Pandoc error: exit 43"
+        );
+
+        // Two-byte characters, so the cut falls inside one unless it backs off
+        let long = "é".repeat(300);
+        let summary = error_summary(&long);
+        assert!(summary.len() <= ERROR_ATTRIBUTE_MAX_BYTES);
+        assert!(summary.ends_with('…'));
+        assert!(summary.trim_end_matches('…').chars().all(|c| c == 'é'));
+        assert_eq!(summary.len(), ERROR_ATTRIBUTE_MAX_BYTES - 1);
+
+        let exact = "x".repeat(ERROR_ATTRIBUTE_MAX_BYTES);
+        assert_eq!(error_summary(&exact), exact);
+    }
+}
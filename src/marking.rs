@@ -0,0 +1,174 @@
+// document-generation-service/src/marking.rs
+
+//! Classification markings: banner text and colors for the page header and
+//! footer, and the abbreviated form used for portion markings.
+
+use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext, RenderError};
+
+/// A classification level recognised at the start of a marking, with the
+/// banner colors conventionally used for it.
+struct Level {
+    name: &'static str,
+    portion: &'static str,
+    background: &'static str,
+    foreground: &'static str,
+}
+
+/// Longer names first, so `TOP SECRET` is not read as `SECRET`.
+const LEVELS: &[Level] = &[
+    Level {
+        name: "TOP SECRET//SCI",
+        portion: "TS//SCI",
+        background: "FCE83A",
+        foreground: "000000",
+    },
+    Level {
+        name: "TOP SECRET",
+        portion: "TS",
+        background: "FF8C00",
+        foreground: "000000",
+    },
+    Level {
+        name: "SECRET",
+        portion: "S",
+        background: "C8102E",
+        foreground: "FFFFFF",
+    },
+    Level {
+        name: "CONFIDENTIAL",
+        portion: "C",
+        background: "0033A0",
+        foreground: "FFFFFF",
+    },
+    Level {
+        name: "CONTROLLED UNCLASSIFIED INFORMATION",
+        portion: "CUI",
+        background: "502B85",
+        foreground: "FFFFFF",
+    },
+    Level {
+        name: "CUI",
+        portion: "CUI",
+        background: "502B85",
+        foreground: "FFFFFF",
+    },
+    Level {
+        name: "UNCLASSIFIED",
+        portion: "U",
+        background: "007A33",
+        foreground: "FFFFFF",
+    },
+];
+
+/// Banner colors for markings that name no known level.
+const OTHER_BACKGROUND: &str = "5A5A5A";
+const OTHER_FOREGROUND: &str = "FFFFFF";
+
+/// A classification marking as shown in banners and portion markings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marking {
+    /// Upper-cased banner text, e.g. `SECRET//NOFORN`.
+    pub text: String,
+    /// Portion marking including the parentheses, e.g. `(S//NOFORN)`.
+    pub portion: String,
+    /// Banner background and text colors as `RRGGBB` hex.
+    pub background: &'static str,
+    pub foreground: &'static str,
+}
+
+impl Marking {
+    pub fn new(classification: &str) -> Self {
+        let text = classification.trim().to_uppercase();
+        let level = LEVELS.iter().find_map(|level| {
+            let rest = text.strip_prefix(level.name)?;
+            (rest.is_empty() || rest.starts_with('/') || rest.starts_with(' '))
+                .then_some((level, rest))
+        });
+
+        match level {
+            Some((level, rest)) => Self {
+                portion: format!("({}{})", level.portion, rest.trim_end()),
+                background: level.background,
+                foreground: level.foreground,
+                text,
+            },
+            None => Self {
+                portion: format!("({})", text),
+                background: OTHER_BACKGROUND,
+                foreground: OTHER_FOREGROUND,
+                text,
+            },
+        }
+    }
+}
+
+/// `{{portion classification}}`: the portion marking for a requirement or
+/// paragraph that carries its own classification, e.g. `(CUI)`.
+pub fn portion_helper(
+    h: &Helper,
+    _: &Handlebars,
+    _: &Context,
+    _: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let classification = h
+        .param(0)
+        .and_then(|param| param.value().as_str())
+        .ok_or_else(|| RenderError::new("portion: expected a classification string"))?;
+    out.write(&Marking::new(classification).portion)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn new_reads_the_level_and_keeps_caveats() {
+        let secret = Marking::new("  secret//noforn ");
+        assert_eq!(secret.text, "SECRET//NOFORN");
+        assert_eq!(secret.portion, "(S//NOFORN)");
+        assert_eq!((secret.background, secret.foreground), ("C8102E", "FFFFFF"));
+
+        let sci = Marking::new("Top Secret//SCI//NOFORN");
+        assert_eq!(sci.portion, "(TS//SCI//NOFORN)");
+        assert_eq!(sci.background, "FCE83A");
+        assert_eq!(Marking::new("TOP SECRET").portion, "(TS)");
+
+        let cui = Marking::new("Controlled Unclassified Information");
+        assert_eq!(cui.portion, "(CUI)");
+        assert_eq!((cui.background, cui.foreground), ("502B85", "FFFFFF"));
+        assert_eq!(Marking::new("Unclassified").portion, "(U)");
+    }
+
+    #[test]
+    fn new_gives_unknown_markings_the_neutral_banner() {
+        // A level name only counts as a whole word
+        let other = Marking::new("Secretariat Internal");
+        assert_eq!(other.text, "SECRETARIAT INTERNAL");
+        assert_eq!(other.portion, "(SECRETARIAT INTERNAL)");
+        assert_eq!(
+            (other.background, other.foreground),
+            (OTHER_BACKGROUND, OTHER_FOREGROUND)
+        );
+    }
+
+    #[test]
+    fn portion_helper_writes_the_portion_marking() {
+        let mut handlebars = Handlebars::new();
+        handlebars.register_helper("portion", Box::new(portion_helper));
+
+        let rendered = handlebars
+            .render_template(
+                "{{portion classification}} The system shall log out idle users.",
+                &json!({ "classification": "confidential" }),
+            )
+            .unwrap();
+        assert_eq!(rendered, "(C) The system shall log out idle users.");
+
+        assert!(handlebars
+            .render_template("{{portion classification}}", &json!({}))
+            .is_err());
+    }
+}
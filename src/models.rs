@@ -1,13 +1,25 @@
 // document-generation-service/src/models.rs
 
+use crate::error::Violation;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DocumentFormat {
     PDF,
     Markdown,
     HTML,
+    DOCX,
+    ODT,
+    EPUB,
+    AsciiDoc,
+    LaTeX,
+    LaTeXBundle,
+    RequirementsJSON,
+    RequirementsCSV,
+    RequirementsXLSX,
+    ReqIF,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,16 +52,73 @@ pub enum SpecificationType {
     ComplianceAuditReport,
     #[serde(rename = "test_execution_report")]
     TestExecutionReport,
+
+    /// Any other type declared in the template manifest.
+    #[serde(untagged)]
+    Custom(String),
+}
+
+impl SpecificationType {
+    /// Key of this type in the template manifest.
+    pub fn as_str(&self) -> &str {
+        match self {
+            SpecificationType::IEEE830DRD => "ieee830_drd",
+            SpecificationType::IEEE830SRS => "ieee830_srs",
+            SpecificationType::MilStd498SRS => "milstd498_srs",
+            SpecificationType::ISO29148StakeholderRequirements => {
+                "iso29148_stakeholder_requirements"
+            }
+            SpecificationType::ISO29148SystemRequirements => "iso29148_system_requirements",
+            SpecificationType::ISO29148SoftwareRequirements => "iso29148_software_requirements",
+            SpecificationType::ISO29148ConceptOfOperations => "iso29148_concept_of_operations",
+            SpecificationType::SecurityScanReport => "security_scan_report",
+            SpecificationType::ComplianceAuditReport => "compliance_audit_report",
+            SpecificationType::TestExecutionReport => "test_execution_report",
+            SpecificationType::Custom(name) => name,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentGenerationRequest {
+    /// Caller-supplied ID echoed back as the response `request_id`.
+    #[serde(default)]
+    pub request_id: Option<String>,
+    /// Caller-supplied ID for correlating the response with upstream work.
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// Tenant whose HTML theme applies when `metadata.theme` is unset.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
     pub specification_type: SpecificationType,
     pub output_formats: Vec<DocumentFormat>,
+    #[serde(default)]
     pub data: serde_json::Value,
+    /// Requirements file converted into `data` before generation.
+    #[serde(default)]
+    pub import: Option<SpecificationImport>,
     pub metadata: DocumentMetadata,
 }
 
+/// Requirements file exported from a requirements tool (DOORS, Polarion,
+/// a spreadsheet), supplied inline or as an object in the storage bucket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpecificationImport {
+    pub format: ImportFormat,
+    #[serde(default)]
+    pub content_base64: Option<String>,
+    /// `gs://{bucket}/{path}` or a path within the configured bucket.
+    #[serde(default)]
+    pub gcs_path: Option<String>,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ImportFormat {
+    ReqIF,
+    CSV,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentMetadata {
     pub title: String,
@@ -61,6 +130,12 @@ pub struct DocumentMetadata {
     pub distribution_statement: Option<String>,
     #[serde(default = "Utc::now")]
     pub generated_date: DateTime<Utc>,
+    /// Bundled HTML theme; the tenant's or the default theme when unset.
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// PDF layout profile; the template's or the default profile when unset.
+    #[serde(default)]
+    pub layout: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,13 +147,35 @@ pub struct GeneratedDocument {
     pub size_bytes: usize,
 }
 
+/// Reference to an artifact stored in the bucket, returned in place of
+/// `GeneratedDocument` when the service runs in pipeline mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactReference {
+    pub artifact_id: i64,
+    pub format: String,
+    pub filename: String,
+    pub gcs_path: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub sha256_checksum: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentGenerationResponse {
     pub request_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
     pub status: String,
     pub documents: Vec<GeneratedDocument>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<ArtifactReference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Schema violations in the request data, when that is why it failed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<Violation>,
     pub generated_at: DateTime<Utc>,
 }
 
@@ -86,9 +183,27 @@ impl DocumentGenerationResponse {
     pub fn success(request_id: String, documents: Vec<GeneratedDocument>) -> Self {
         Self {
             request_id,
+            correlation_id: None,
             status: "success".to_string(),
             documents,
+            document_id: None,
+            artifacts: vec![],
+            error: None,
+            violations: vec![],
+            generated_at: Utc::now(),
+        }
+    }
+
+    pub fn stored(request_id: String, document_id: i64, artifacts: Vec<ArtifactReference>) -> Self {
+        Self {
+            request_id,
+            correlation_id: None,
+            status: "success".to_string(),
+            documents: vec![],
+            document_id: Some(document_id),
+            artifacts,
             error: None,
+            violations: vec![],
             generated_at: Utc::now(),
         }
     }
@@ -96,9 +211,13 @@ impl DocumentGenerationResponse {
     pub fn error(request_id: String, error: String) -> Self {
         Self {
             request_id,
+            correlation_id: None,
             status: "error".to_string(),
             documents: vec![],
+            document_id: None,
+            artifacts: vec![],
             error: Some(error),
+            violations: vec![],
             generated_at: Utc::now(),
         }
     }
@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool, Row};
+use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
 
 // ============================================================
@@ -54,6 +54,10 @@ pub struct DocumentTemplate {
     pub template_type: String,
     pub format: String,
     pub template_content: String,
+    /// PDF layout profile for documents rendered from this template; read
+    /// as unset where the column does not exist.
+    #[sqlx(default)]
+    pub layout_profile: Option<String>,
     pub schema_version: String,
     pub is_system: bool,
     pub is_active: bool,
@@ -155,21 +159,6 @@ impl DocumentDb {
         Ok(doc)
     }
 
-    pub async fn get_document(&self, tenant_id: Uuid, id: i64) -> Result<Option<GeneratedDocument>> {
-        let mut conn = self.pool.acquire().await?;
-        Self::set_tenant_context(&mut conn, tenant_id).await?;
-
-        let doc = sqlx::query_as::<_, GeneratedDocument>(
-            "SELECT * FROM storage.generated_documents WHERE id = $1",
-        )
-        .bind(id)
-        .fetch_optional(&mut *conn)
-        .await
-        .context("Failed to fetch generated_document")?;
-
-        Ok(doc)
-    }
-
     pub async fn update_document_status(
         &self,
         tenant_id: Uuid,
@@ -214,68 +203,6 @@ impl DocumentDb {
         Ok(doc)
     }
 
-    pub async fn delete_document(&self, tenant_id: Uuid, id: i64) -> Result<bool> {
-        let mut conn = self.pool.acquire().await?;
-        Self::set_tenant_context(&mut conn, tenant_id).await?;
-
-        let result = sqlx::query("DELETE FROM storage.generated_documents WHERE id = $1")
-            .bind(id)
-            .execute(&mut *conn)
-            .await
-            .context("Failed to delete generated_document")?;
-
-        Ok(result.rows_affected() > 0)
-    }
-
-    pub async fn list_documents(
-        &self,
-        tenant_id: Uuid,
-        project_id: Option<i64>,
-        document_type: Option<&str>,
-        status: Option<&str>,
-        limit: i64,
-        offset: i64,
-    ) -> Result<(Vec<GeneratedDocument>, i64)> {
-        let mut conn = self.pool.acquire().await?;
-        Self::set_tenant_context(&mut conn, tenant_id).await?;
-
-        let count = sqlx::query_scalar::<_, i64>(
-            r#"
-            SELECT COUNT(*) FROM storage.generated_documents
-            WHERE ($1::bigint IS NULL OR project_id = $1)
-              AND ($2::text IS NULL OR document_type = $2)
-              AND ($3::text IS NULL OR status = $3)
-            "#,
-        )
-        .bind(project_id)
-        .bind(document_type)
-        .bind(status)
-        .fetch_one(&mut *conn)
-        .await
-        .context("Failed to count documents")?;
-
-        let docs = sqlx::query_as::<_, GeneratedDocument>(
-            r#"
-            SELECT * FROM storage.generated_documents
-            WHERE ($1::bigint IS NULL OR project_id = $1)
-              AND ($2::text IS NULL OR document_type = $2)
-              AND ($3::text IS NULL OR status = $3)
-            ORDER BY created_at DESC
-            LIMIT $4 OFFSET $5
-            "#,
-        )
-        .bind(project_id)
-        .bind(document_type)
-        .bind(status)
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&mut *conn)
-        .await
-        .context("Failed to list documents")?;
-
-        Ok((docs, count))
-    }
-
     // --------------------------------------------------------
     // generated_document_artifacts CRUD
     // --------------------------------------------------------
@@ -312,63 +239,6 @@ impl DocumentDb {
         Ok(artifact)
     }
 
-    pub async fn list_artifacts(
-        &self,
-        tenant_id: Uuid,
-        document_id: i64,
-    ) -> Result<Vec<DocumentArtifact>> {
-        let mut conn = self.pool.acquire().await?;
-        Self::set_tenant_context(&mut conn, tenant_id).await?;
-
-        let artifacts = sqlx::query_as::<_, DocumentArtifact>(
-            "SELECT * FROM storage.generated_document_artifacts WHERE document_id = $1 ORDER BY format",
-        )
-        .bind(document_id)
-        .fetch_all(&mut *conn)
-        .await
-        .context("Failed to list artifacts")?;
-
-        Ok(artifacts)
-    }
-
-    pub async fn get_artifact(
-        &self,
-        tenant_id: Uuid,
-        artifact_id: i64,
-    ) -> Result<Option<DocumentArtifact>> {
-        let mut conn = self.pool.acquire().await?;
-        Self::set_tenant_context(&mut conn, tenant_id).await?;
-
-        let artifact = sqlx::query_as::<_, DocumentArtifact>(
-            "SELECT * FROM storage.generated_document_artifacts WHERE id = $1",
-        )
-        .bind(artifact_id)
-        .fetch_optional(&mut *conn)
-        .await
-        .context("Failed to fetch artifact")?;
-
-        Ok(artifact)
-    }
-
-    pub async fn delete_artifacts_for_document(
-        &self,
-        tenant_id: Uuid,
-        document_id: i64,
-    ) -> Result<Vec<String>> {
-        let mut conn = self.pool.acquire().await?;
-        Self::set_tenant_context(&mut conn, tenant_id).await?;
-
-        let paths: Vec<String> = sqlx::query_scalar(
-            "DELETE FROM storage.generated_document_artifacts WHERE document_id = $1 RETURNING gcs_path",
-        )
-        .bind(document_id)
-        .fetch_all(&mut *conn)
-        .await
-        .context("Failed to delete artifacts")?;
-
-        Ok(paths)
-    }
-
     // --------------------------------------------------------
     // document_templates
     // --------------------------------------------------------
@@ -4,9 +4,29 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, info, instrument};
 use uuid::Uuid;
 
-use crate::gcs::{DocumentStorage, RenderedFile, UploadResult};
-use crate::persistence::{CreateArtifactInput, CreateDocumentInput, DocumentDb, GeneratedDocument};
-use crate::renderer::DocumentRenderer; // existing Handlebars + Pandoc renderer
+use crate::error::DocumentError;
+use crate::exports::reqif;
+use crate::exports::requirements::RequirementSet;
+use crate::gcs::{DocumentStorage, RenderedFile};
+use crate::generators::{create_generator, TemplateRegistry};
+use crate::ingest;
+use crate::marking;
+use crate::models::{DocumentMetadata, SpecificationImport, SpecificationType};
+use crate::persistence::{
+    CreateArtifactInput, CreateDocumentInput, DocumentArtifact, DocumentDb, DocumentTemplate,
+    GeneratedDocument,
+};
+use crate::config::RenderConfig;
+use crate::renderers::{
+    AsciiDocRenderer, DocxRenderer, EpubRenderer, HtmlRenderer, MarkdownRenderer, OdtRenderer,
+    PdfRenderer,
+};
+use handlebars::Handlebars;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
 
 /// Inbound Pub/Sub message payload for document generation requests.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,33 +34,131 @@ pub struct DocumentGenerationRequest {
     pub tenant_id: Uuid,
     pub project_id: i64,
     pub template_id: Option<i64>,
-    pub correlation_id: Option<Uuid>,
+    /// Caller's ID, echoed in the response; persisted only when it is a UUID.
+    pub correlation_id: Option<String>,
     pub title: String,
     pub document_type: String,
     pub requested_formats: Vec<String>,
     pub input_params: serde_json::Value,
+    /// Requirements file converted into `input_params` before rendering.
+    #[serde(default)]
+    pub import: Option<SpecificationImport>,
     pub requested_by: i64,
 }
 
+/// Final document record together with the artifacts persisted for it.
+#[derive(Debug)]
+pub struct PipelineOutcome {
+    pub document: GeneratedDocument,
+    pub artifacts: Vec<DocumentArtifact>,
+    /// Why the document failed, so the caller can tell a retryable fault
+    /// from a bad request.
+    pub error: Option<DocumentError>,
+}
+
+/// Where the Markdown body for a document comes from.
+enum TemplateSource {
+    /// Tenant template stored in `storage.document_templates`, with the PDF
+    /// layout profile it names.
+    Stored {
+        content: String,
+        layout: Option<String>,
+    },
+    /// Built-in generator for a known specification type.
+    Builtin(SpecificationType),
+}
+
+impl From<DocumentTemplate> for TemplateSource {
+    fn from(tpl: DocumentTemplate) -> Self {
+        TemplateSource::Stored {
+            content: tpl.template_content,
+            layout: tpl.layout_profile,
+        }
+    }
+}
+
+impl TemplateSource {
+    /// PDF layout profile the template asks for, if any.
+    fn layout(&self, templates: &TemplateRegistry) -> Option<String> {
+        match self {
+            TemplateSource::Stored { layout, .. } => layout.clone(),
+            TemplateSource::Builtin(spec_type) => templates.entry(spec_type.as_str()).ok()?.layout,
+        }
+    }
+}
+
 /// Orchestrates: create record → render → upload → persist artifacts → mark complete.
 pub struct DocumentPipeline {
     db: DocumentDb,
     storage: DocumentStorage,
-    renderer: DocumentRenderer,
+    templates: Arc<TemplateRegistry>,
+    handlebars: Handlebars<'static>,
+    pdf_renderer: PdfRenderer,
+    markdown_renderer: MarkdownRenderer,
+    html_renderer: HtmlRenderer,
+    docx_renderer: DocxRenderer,
+    odt_renderer: OdtRenderer,
+    epub_renderer: EpubRenderer,
+    asciidoc_renderer: AsciiDocRenderer,
 }
 
 impl DocumentPipeline {
-    pub fn new(db: DocumentDb, storage: DocumentStorage, renderer: DocumentRenderer) -> Self {
-        Self { db, storage, renderer }
+    pub fn new(
+        db: DocumentDb,
+        storage: DocumentStorage,
+        templates: Arc<TemplateRegistry>,
+        render: &RenderConfig,
+        render_permits: Arc<Semaphore>,
+    ) -> Self {
+        let mut handlebars = Handlebars::new();
+        handlebars.set_strict_mode(false);
+        handlebars.register_helper("portion", Box::new(marking::portion_helper));
+
+        Self {
+            db,
+            storage,
+            templates,
+            handlebars,
+            pdf_renderer: PdfRenderer::new(render_permits.clone(), render.timeout_seconds),
+            markdown_renderer: MarkdownRenderer::new(),
+            html_renderer: HtmlRenderer::new(
+                render_permits.clone(),
+                Duration::from_secs(render.timeout_seconds.html),
+            ),
+            docx_renderer: DocxRenderer::new(
+                render_permits.clone(),
+                render.docx_reference_doc.as_ref().map(PathBuf::from),
+                Duration::from_secs(render.timeout_seconds.docx),
+            ),
+            odt_renderer: OdtRenderer::new(
+                render_permits.clone(),
+                render.odt_reference_doc.as_ref().map(PathBuf::from),
+                Duration::from_secs(render.timeout_seconds.odt),
+            ),
+            epub_renderer: EpubRenderer::new(
+                render_permits.clone(),
+                render.epub_cover_image.as_ref().map(PathBuf::from),
+                Duration::from_secs(render.timeout_seconds.epub),
+            ),
+            asciidoc_renderer: AsciiDocRenderer::new(
+                render_permits,
+                Duration::from_secs(render.timeout_seconds.asciidoc),
+            ),
+        }
     }
 
-    /// Main entry point called from the Pub/Sub message handler.
+    /// Main entry point called from the Pub/Sub message handler. Pandoc runs
+    /// are killed when `cancel` fires and the document is marked failed.
     #[instrument(skip(self, req), fields(
         tenant_id = %req.tenant_id,
         project_id = req.project_id,
         doc_type = %req.document_type
     ))]
-    pub async fn process(&self, req: DocumentGenerationRequest) -> Result<GeneratedDocument> {
+    pub async fn process(
+        &self,
+        mut req: DocumentGenerationRequest,
+        cancel: &CancellationToken,
+    ) -> Result<PipelineOutcome> {
         // 1. Insert document record as 'queued'
         let doc = self
             .db
@@ -48,7 +166,10 @@ impl DocumentPipeline {
                 tenant_id: req.tenant_id,
                 project_id: req.project_id,
                 template_id: req.template_id,
-                correlation_id: req.correlation_id,
+                correlation_id: req
+                    .correlation_id
+                    .as_deref()
+                    .and_then(|id| Uuid::parse_str(id).ok()),
                 title: req.title.clone(),
                 document_type: req.document_type.clone(),
                 requested_formats: req.requested_formats.clone(),
@@ -65,46 +186,82 @@ impl DocumentPipeline {
             .update_document_status(req.tenant_id, doc.id, "processing", None, None)
             .await?;
 
-        // 3. Resolve template
-        let template_content = if let Some(tid) = req.template_id {
-            let tpl = self
-                .db
-                .get_template(req.tenant_id, tid)
-                .await?
-                .context("Requested template not found")?;
-            tpl.template_content
-        } else {
-            // Fall back to default template for this document type
-            let tpl = self
-                .db
-                .get_template_by_type(req.tenant_id, &req.document_type, "pdf")
-                .await?
-                .context("No default template found for document type")?;
-            tpl.template_content
+        // 3. Convert an attached requirements file into the input parameters
+        if let Some(import) = req.import.take() {
+            match ingest::requirements::import(
+                &req.document_type,
+                &req.input_params,
+                &import,
+                Some(&req.tenant_id.to_string()),
+                Some(&self.storage),
+            )
+            .await
+            {
+                Ok(params) => req.input_params = params,
+                Err(e) => {
+                    let err_msg = format!("Requirements import failed: {e}");
+                    error!(document_id = doc.id, error = %err_msg, "Import failure");
+                    return self.fail(&req, doc.id, &err_msg, e).await;
+                }
+            }
+        }
+
+        // 4. Resolve template
+        let source = match self.resolve_template(&req).await {
+            Ok(source) => source,
+            Err(e) => {
+                let err_msg = format!("Template resolution failed: {e:#}");
+                error!(document_id = doc.id, error = %err_msg, "Template failure");
+                return self.fail(&req, doc.id, &err_msg, classify(e)).await;
+            }
         };
 
-        // 4. Render all requested formats
+        // 5. Render all requested formats
         self.db
             .update_document_status(req.tenant_id, doc.id, "rendering", None, None)
             .await?;
 
+        let mut metadata = match document_metadata(&req) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                let err_msg = format!("Invalid document metadata: {e}");
+                error!(document_id = doc.id, error = %err_msg, "Metadata failure");
+                return self.fail(&req, doc.id, &err_msg, e).await;
+            }
+        };
+        // The tenant's HTML theme applies unless the request picked one
+        if metadata.theme.is_none() {
+            let tenant = req.tenant_id.to_string();
+            metadata.theme = self
+                .templates
+                .theme(None, Some(&tenant))
+                .ok()
+                .flatten()
+                .map(|theme| theme.name);
+        }
+        if metadata.layout.is_none() {
+            metadata.layout = source.layout(&self.templates);
+        }
         let rendered_files = match self
-            .render_all_formats(&template_content, &req.input_params, &req.requested_formats, &req.title)
+            .render_all_formats(
+                &source,
+                &req.document_type,
+                &req.input_params,
+                &metadata,
+                &req.requested_formats,
+                cancel,
+            )
             .await
         {
             Ok(files) => files,
             Err(e) => {
                 let err_msg = format!("Rendering failed: {e:#}");
                 error!(document_id = doc.id, error = %err_msg, "Render failure");
-                let failed = self
-                    .db
-                    .update_document_status(req.tenant_id, doc.id, "failed", Some(&err_msg), None)
-                    .await?;
-                return Ok(failed);
+                return self.fail(&req, doc.id, &err_msg, classify(e)).await;
             }
         };
 
-        // 5. Upload to GCS
+        // 6. Upload to GCS
         self.db
             .update_document_status(req.tenant_id, doc.id, "uploading", None, None)
             .await?;
@@ -118,17 +275,15 @@ impl DocumentPipeline {
             Err(e) => {
                 let err_msg = format!("GCS upload failed: {e:#}");
                 error!(document_id = doc.id, error = %err_msg, "Upload failure");
-                let failed = self
-                    .db
-                    .update_document_status(req.tenant_id, doc.id, "failed", Some(&err_msg), None)
-                    .await?;
-                return Ok(failed);
+                return self.fail(&req, doc.id, &err_msg, classify(e)).await;
             }
         };
 
-        // 6. Persist artifact metadata rows
+        // 7. Persist artifact metadata rows
+        let mut artifacts = Vec::with_capacity(upload_results.len());
         for result in &upload_results {
-            self.db
+            let artifact = self
+                .db
                 .create_artifact(&CreateArtifactInput {
                     tenant_id: req.tenant_id,
                     document_id: doc.id,
@@ -145,9 +300,10 @@ impl DocumentPipeline {
                 .with_context(|| {
                     format!("Failed to persist artifact metadata for {}", result.format)
                 })?;
+            artifacts.push(artifact);
         }
 
-        // 7. Build generation metadata
+        // 8. Build generation metadata
         let gen_metadata = serde_json::json!({
             "rendering_engine": "pandoc-xelatex",
             "template_engine": "handlebars",
@@ -156,7 +312,7 @@ impl DocumentPipeline {
             "completed_at": Utc::now().to_rfc3339(),
         });
 
-        // 8. Mark completed
+        // 9. Mark completed
         let completed = self
             .db
             .update_document_status(
@@ -174,17 +330,110 @@ impl DocumentPipeline {
             "Document generation completed successfully"
         );
 
-        Ok(completed)
+        Ok(PipelineOutcome {
+            document: completed,
+            artifacts,
+            error: None,
+        })
     }
 
-    /// Render the template into each requested format via the existing renderer.
+    /// Mark the document as failed and return it without artifacts.
+    async fn fail(
+        &self,
+        req: &DocumentGenerationRequest,
+        document_id: i64,
+        err_msg: &str,
+        error: DocumentError,
+    ) -> Result<PipelineOutcome> {
+        let failed = self
+            .db
+            .update_document_status(req.tenant_id, document_id, "failed", Some(err_msg), None)
+            .await?;
+
+        Ok(PipelineOutcome {
+            document: failed,
+            artifacts: vec![],
+            error: Some(error),
+        })
+    }
+
+    /// Pick the template for a request: an explicit tenant template, then the
+    /// tenant's default for the document type, then the built-in generator.
+    async fn resolve_template(&self, req: &DocumentGenerationRequest) -> Result<TemplateSource> {
+        if let Some(tid) = req.template_id {
+            let tpl = self
+                .db
+                .get_template(req.tenant_id, tid)
+                .await?
+                .ok_or_else(|| DocumentError::TemplateNotFound(format!("template {}", tid)))?;
+            return Ok(TemplateSource::from(tpl));
+        }
+
+        if let Some(tpl) = self
+            .db
+            .get_template_by_type(req.tenant_id, &req.document_type, "pdf")
+            .await?
+        {
+            return Ok(TemplateSource::from(tpl));
+        }
+
+        let spec_type: SpecificationType =
+            serde_json::from_value(serde_json::Value::String(req.document_type.clone()))?;
+        self.templates.entry(spec_type.as_str()).with_context(|| {
+            format!("No template found for document type {}", req.document_type)
+        })?;
+
+        Ok(TemplateSource::Builtin(spec_type))
+    }
+
+    /// Produce the Markdown body from either a stored or a built-in template.
+    async fn render_markdown(
+        &self,
+        source: &TemplateSource,
+        input_params: &serde_json::Value,
+        metadata: &DocumentMetadata,
+    ) -> Result<String> {
+        match source {
+            TemplateSource::Stored { content, .. } => {
+                let mut context = serde_json::json!({
+                    "metadata": metadata,
+                    "data": input_params,
+                });
+
+                // Merge if data is an object
+                if let (serde_json::Value::Object(map), serde_json::Value::Object(ctx_map)) =
+                    (input_params, &mut context)
+                {
+                    for (key, value) in map {
+                        ctx_map.insert(key.clone(), value.clone());
+                    }
+                }
+
+                Ok(self.handlebars.render_template(content, &context)?)
+            }
+            TemplateSource::Builtin(spec_type) => {
+                let generator = create_generator(spec_type, &self.templates)?;
+                let violations = self.templates.validate(spec_type.as_str(), input_params)?;
+                if !violations.is_empty() {
+                    anyhow::bail!(DocumentError::from_violations(&violations));
+                }
+                Ok(generator.generate(input_params, metadata).await?)
+            }
+        }
+    }
+
+    /// Render the template into each requested format via the existing renderers.
     async fn render_all_formats(
         &self,
-        template_content: &str,
+        source: &TemplateSource,
+        document_type: &str,
         input_params: &serde_json::Value,
+        metadata: &DocumentMetadata,
         formats: &[String],
-        title: &str,
+        cancel: &CancellationToken,
     ) -> Result<Vec<RenderedFile>> {
+        let markdown = self.render_markdown(source, input_params, metadata).await?;
+        let requirements = || RequirementSet::from_data(document_type, input_params, metadata);
         let mut files = Vec::with_capacity(formats.len());
 
         for fmt in formats {
@@ -192,23 +441,88 @@ impl DocumentPipeline {
 
             let (data, content_type, extension) = match fmt.as_str() {
                 "pdf" => {
-                    let html = self.renderer.render_handlebars(template_content, input_params)?;
-                    let pdf = self.renderer.html_to_pdf(&html).await?;
+                    let layout = self.templates.pdf_layout(metadata.layout.as_deref())?;
+                    let pdf = self
+                        .pdf_renderer
+                        .render(&markdown, metadata, &layout, cancel)
+                        .await?;
                     (pdf, "application/pdf".to_string(), "pdf")
                 }
                 "html" => {
-                    let html = self.renderer.render_handlebars(template_content, input_params)?;
-                    (html.into_bytes(), "text/html; charset=utf-8".to_string(), "html")
+                    let theme = self.templates.theme(metadata.theme.as_deref(), None)?;
+                    let html = self
+                        .html_renderer
+                        .render(&markdown, metadata, theme.as_ref(), cancel)
+                        .await?;
+                    (html, "text/html; charset=utf-8".to_string(), "html")
                 }
                 "markdown" => {
-                    let md = self.renderer.render_handlebars_markdown(template_content, input_params)?;
-                    (md.into_bytes(), "text/markdown; charset=utf-8".to_string(), "md")
+                    let md = self.markdown_renderer.render(&markdown, metadata).await?;
+                    (md, "text/markdown; charset=utf-8".to_string(), "md")
                 }
-                other => anyhow::bail!("Unsupported format: {}", other),
+                "docx" => {
+                    let docx = self.docx_renderer.render(&markdown, metadata, cancel).await?;
+                    (
+                        docx,
+                        "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+                            .to_string(),
+                        "docx",
+                    )
+                }
+                "odt" => {
+                    let odt = self.odt_renderer.render(&markdown, metadata, cancel).await?;
+                    (odt, "application/vnd.oasis.opendocument.text".to_string(), "odt")
+                }
+                "epub" => {
+                    let epub = self.epub_renderer.render(&markdown, metadata, cancel).await?;
+                    (epub, "application/epub+zip".to_string(), "epub")
+                }
+                "asciidoc" => {
+                    let adoc = self.asciidoc_renderer.render(&markdown, metadata, cancel).await?;
+                    (adoc, "text/asciidoc; charset=utf-8".to_string(), "adoc")
+                }
+                "latex" => {
+                    let layout = self.templates.pdf_layout(metadata.layout.as_deref())?;
+                    let tex = self
+                        .pdf_renderer
+                        .render_latex(&markdown, metadata, &layout, cancel)
+                        .await?;
+                    (tex, "application/x-tex; charset=utf-8".to_string(), "tex")
+                }
+                "latex_bundle" => {
+                    let layout = self.templates.pdf_layout(metadata.layout.as_deref())?;
+                    let bundle = self
+                        .pdf_renderer
+                        .render_latex_bundle(&markdown, metadata, &layout, cancel)
+                        .await?;
+                    (bundle, "application/zip".to_string(), "zip")
+                }
+                "requirements_json" => (
+                    requirements()?.to_json()?,
+                    "application/json".to_string(),
+                    "requirements.json",
+                ),
+                "requirements_csv" => (
+                    requirements()?.to_csv()?,
+                    "text/csv; charset=utf-8".to_string(),
+                    "requirements.csv",
+                ),
+                "requirements_xlsx" => (
+                    requirements()?.to_xlsx()?,
+                    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string(),
+                    "requirements.xlsx",
+                ),
+                "reqif" => (
+                    reqif::to_reqif(&requirements()?, metadata)?,
+                    "application/xml; charset=utf-8".to_string(),
+                    "reqif",
+                ),
+                other => anyhow::bail!(DocumentError::InvalidFormat(other.to_string())),
             };
 
             let duration_ms = start.elapsed().as_millis() as i32;
-            let sanitized_title = title
+            let sanitized_title = metadata
+                .title
                 .chars()
                 .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
                 .collect::<String>();
@@ -226,3 +540,92 @@ impl DocumentPipeline {
         Ok(files)
     }
 }
+
+/// The `DocumentError` behind a pipeline failure. Postgres and bucket faults
+/// are reported as `StorageError`, so they are retried; any other error is a
+/// deterministic failure of this document and becomes `GenerationFailed`, so
+/// it is answered once instead of redelivered until it dead-letters.
+pub fn classify(e: anyhow::Error) -> DocumentError {
+    let e = match e.downcast::<DocumentError>() {
+        Ok(e) => return e,
+        Err(e) => e,
+    };
+    let e = match e.downcast::<serde_json::Error>() {
+        Ok(e) => return e.into(),
+        Err(e) => e,
+    };
+    let e = match e.downcast::<handlebars::RenderError>() {
+        Ok(e) => return e.into(),
+        Err(e) => e,
+    };
+    let storage_fault = e
+        .chain()
+        .any(|cause| cause.is::<sqlx::Error>() || cause.is::<google_cloud_storage::http::Error>());
+    if storage_fault {
+        DocumentError::StorageError(format!("{e:#}"))
+    } else {
+        DocumentError::GenerationFailed(format!("{e:#}"))
+    }
+}
+
+/// Document metadata for the renderers. Callers may pass a full `metadata`
+/// object inside `input_params`; otherwise only the title is known. A
+/// `metadata` object that does not parse is rejected rather than replaced,
+/// since it may carry the classification.
+fn document_metadata(
+    req: &DocumentGenerationRequest,
+) -> std::result::Result<DocumentMetadata, DocumentError> {
+    if let Some(metadata) = req.input_params.get("metadata") {
+        return serde_json::from_value(metadata.clone()).map_err(|e| {
+            DocumentError::InvalidData(format!("input_params.metadata: {}", e))
+        });
+    }
+    Ok(DocumentMetadata {
+        title: req.title.clone(),
+        project_name: req.project_id.to_string(),
+        version: String::new(),
+        author: String::new(),
+        organization: String::new(),
+        classification: None,
+        distribution_statement: None,
+        generated_date: Utc::now(),
+        theme: None,
+        layout: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn classify_retries_only_database_and_bucket_faults() {
+        let database =
+            anyhow!(sqlx::Error::PoolTimedOut).context("Failed to create artifact record");
+        assert!(matches!(classify(database), DocumentError::StorageError(_)));
+
+        let bucket = anyhow!(google_cloud_storage::http::Error::Response(
+            google_cloud_storage::http::error::ErrorResponse {
+                code: 503,
+                errors: Vec::new(),
+                message: "Backend Error".to_string(),
+            }
+        ))
+        .context("Failed to upload report.pdf");
+        assert!(classify(bucket).is_retryable());
+
+        let render = anyhow!(DocumentError::PandocError("killed".to_string())).context("Rendering");
+        assert!(matches!(classify(render), DocumentError::PandocError(_)));
+
+        for deterministic in [
+            anyhow!(std::io::Error::from(std::io::ErrorKind::NotFound)),
+            anyhow!(zip::result::ZipError::FileNotFound),
+            anyhow!("Template 12 not found for tenant"),
+        ] {
+            let e = classify(deterministic.context("Rendering failed"));
+            assert!(matches!(e, DocumentError::GenerationFailed(_)), "{e:?}");
+            assert!(!e.is_retryable());
+        }
+    }
+}
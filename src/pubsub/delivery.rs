@@ -0,0 +1,144 @@
+// document-generation-service/src/pubsub/delivery.rs
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Messages tracked at once; the least recently seen are dropped beyond this.
+const MAX_TRACKED: usize = 10_000;
+
+/// Entries not delivered again within this long are dropped; the message was
+/// most likely redelivered to another pod.
+const ENTRY_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Counts delivery attempts per message so retryable failures can be
+/// dead-lettered after a bounded number of redeliveries.
+///
+/// Pub/Sub only reports `delivery_attempt` when the subscription itself has a
+/// dead-letter policy; otherwise attempts are counted locally by message ID.
+#[derive(Default)]
+pub struct DeliveryTracker {
+    attempts: Mutex<HashMap<String, Attempts>>,
+}
+
+struct Attempts {
+    count: usize,
+    last_seen: Instant,
+}
+
+impl DeliveryTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a delivery and return its attempt number, starting at 1.
+    pub fn record(&self, message_id: &str, reported: Option<usize>) -> usize {
+        let mut attempts = self.attempts.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        if attempts.len() >= MAX_TRACKED && !attempts.contains_key(message_id) {
+            attempts.retain(|_, entry| now.duration_since(entry.last_seen) < ENTRY_TTL);
+            while attempts.len() >= MAX_TRACKED {
+                let Some(oldest) = attempts
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_seen)
+                    .map(|(id, _)| id.clone())
+                else {
+                    break;
+                };
+                attempts.remove(&oldest);
+            }
+        }
+
+        let entry = attempts.entry(message_id.to_string()).or_insert(Attempts {
+            count: 0,
+            last_seen: now,
+        });
+        entry.count = match reported {
+            Some(reported) => reported.max(entry.count + 1),
+            None => entry.count + 1,
+        };
+        entry.last_seen = now;
+        entry.count
+    }
+
+    /// Forget a message once it has been acked or dead-lettered.
+    pub fn forget(&self, message_id: &str) {
+        let mut attempts = self.attempts.lock().unwrap_or_else(|e| e.into_inner());
+        attempts.remove(message_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tracker already holding `count` entries last seen `age` ago.
+    fn tracker_with(count: usize, prefix: &str, age: Duration) -> DeliveryTracker {
+        let tracker = DeliveryTracker::new();
+        let last_seen = Instant::now().checked_sub(age).unwrap();
+        tracker.attempts.lock().unwrap().extend((0..count).map(|i| {
+            (
+                format!("{}-{}", prefix, i),
+                Attempts {
+                    count: 1,
+                    last_seen,
+                },
+            )
+        }));
+        tracker
+    }
+
+    #[test]
+    fn record_counts_attempts_per_message() {
+        let tracker = DeliveryTracker::new();
+
+        assert_eq!(tracker.record("a", None), 1);
+        assert_eq!(tracker.record("a", None), 2);
+        assert_eq!(tracker.record("b", None), 1);
+        // The subscription's own count wins when it is ahead, never when behind
+        assert_eq!(tracker.record("a", Some(5)), 5);
+        assert_eq!(tracker.record("a", Some(1)), 6);
+
+        tracker.forget("a");
+        assert_eq!(tracker.record("a", None), 1);
+    }
+
+    #[test]
+    fn record_drops_stale_entries_first_at_the_cap() {
+        let tracker = tracker_with(MAX_TRACKED / 2, "stale", ENTRY_TTL * 2);
+        let fresh = tracker_with(MAX_TRACKED / 2, "fresh", Duration::from_secs(1));
+        tracker
+            .attempts
+            .lock()
+            .unwrap()
+            .extend(fresh.attempts.into_inner().unwrap());
+
+        assert_eq!(tracker.record("new", None), 1);
+
+        let attempts = tracker.attempts.lock().unwrap();
+        assert_eq!(attempts.len(), MAX_TRACKED / 2 + 1);
+        assert!(attempts.keys().all(|id| !id.starts_with("stale")));
+    }
+
+    #[test]
+    fn record_evicts_the_least_recently_seen_beyond_the_cap() {
+        let tracker = tracker_with(MAX_TRACKED - 1, "recent", Duration::from_secs(1));
+        let oldest = Instant::now().checked_sub(Duration::from_secs(60)).unwrap();
+        tracker.attempts.lock().unwrap().insert(
+            "oldest".to_string(),
+            Attempts {
+                count: 3,
+                last_seen: oldest,
+            },
+        );
+
+        assert_eq!(tracker.record("new", None), 1);
+        // A message already tracked never evicts another
+        assert_eq!(tracker.record("recent-0", None), 2);
+
+        let attempts = tracker.attempts.lock().unwrap();
+        assert_eq!(attempts.len(), MAX_TRACKED);
+        assert!(!attempts.contains_key("oldest"));
+        assert!(attempts.contains_key("new"));
+    }
+}
@@ -1,43 +1,201 @@
 // document-generation-service/src/pubsub/handler.rs
 
-use crate::error::Result;
+use crate::config::RenderConfig;
+use crate::error::{DocumentError, Result};
+use crate::exports::reqif;
+use crate::exports::requirements::RequirementSet;
+use crate::gcs::DocumentStorage;
+use crate::generators::{create_generator, PdfLayout, TemplateRegistry};
+use crate::ingest;
 use crate::models::{
-    DocumentFormat, DocumentGenerationRequest, DocumentGenerationResponse, GeneratedDocument,
+    ArtifactReference, DocumentFormat, DocumentGenerationRequest, DocumentGenerationResponse,
+    GeneratedDocument,
+};
+use crate::pipeline::{self, DocumentPipeline};
+use crate::renderers::{
+    AsciiDocRenderer, DocxRenderer, EpubRenderer, HtmlRenderer, MarkdownRenderer, OdtRenderer,
+    PdfRenderer,
 };
-use crate::renderers::{HtmlRenderer, MarkdownRenderer, PdfRenderer};
 use base64::{engine::general_purpose, Engine as _};
+use google_cloud_googleapis::pubsub::v1::PubsubMessage;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
+/// Response to publish for a message, together with the error that produced
+/// it so the subscriber can choose between ack, nack and dead-lettering.
+#[derive(Debug)]
+pub struct HandlerOutcome {
+    pub response: DocumentGenerationResponse,
+    pub error: Option<DocumentError>,
+}
+
+impl HandlerOutcome {
+    fn success(response: DocumentGenerationResponse) -> Self {
+        Self {
+            response,
+            error: None,
+        }
+    }
+
+    fn failure(request_id: String, error: DocumentError) -> Self {
+        Self {
+            response: DocumentGenerationResponse::error(request_id, error.to_string()),
+            error: Some(error),
+        }
+    }
+
+    /// Rejection of a body that does not parse, still echoing the
+    /// publisher's `correlation_id` attribute.
+    fn unparseable(request_id: String, message: &PubsubMessage, error: serde_json::Error) -> Self {
+        let mut response = DocumentGenerationResponse::error(
+            request_id,
+            format!("Invalid request format: {}", error),
+        );
+        response.correlation_id = attribute(message, "correlation_id");
+        Self {
+            response,
+            error: Some(error.into()),
+        }
+    }
+}
+
 pub struct MessageHandler {
     pdf_renderer: PdfRenderer,
     markdown_renderer: MarkdownRenderer,
     html_renderer: HtmlRenderer,
+    docx_renderer: DocxRenderer,
+    odt_renderer: OdtRenderer,
+    epub_renderer: EpubRenderer,
+    asciidoc_renderer: AsciiDocRenderer,
+    templates: Arc<TemplateRegistry>,
+    /// Bucket that `import.gcs_path` files are read from.
+    storage: Option<DocumentStorage>,
+    pipeline: Option<DocumentPipeline>,
 }
 
 impl MessageHandler {
-    pub fn new() -> Self {
+    pub fn new(
+        templates: Arc<TemplateRegistry>,
+        render: &RenderConfig,
+        render_permits: Arc<Semaphore>,
+        storage: Option<DocumentStorage>,
+    ) -> Self {
         Self {
-            pdf_renderer: PdfRenderer::new(),
+            pdf_renderer: PdfRenderer::new(render_permits.clone(), render.timeout_seconds),
             markdown_renderer: MarkdownRenderer::new(),
-            html_renderer: HtmlRenderer::new(),
+            html_renderer: HtmlRenderer::new(
+                render_permits.clone(),
+                Duration::from_secs(render.timeout_seconds.html),
+            ),
+            docx_renderer: DocxRenderer::new(
+                render_permits.clone(),
+                render.docx_reference_doc.as_ref().map(PathBuf::from),
+                Duration::from_secs(render.timeout_seconds.docx),
+            ),
+            odt_renderer: OdtRenderer::new(
+                render_permits.clone(),
+                render.odt_reference_doc.as_ref().map(PathBuf::from),
+                Duration::from_secs(render.timeout_seconds.odt),
+            ),
+            epub_renderer: EpubRenderer::new(
+                render_permits.clone(),
+                render.epub_cover_image.as_ref().map(PathBuf::from),
+                Duration::from_secs(render.timeout_seconds.epub),
+            ),
+            asciidoc_renderer: AsciiDocRenderer::new(
+                render_permits,
+                Duration::from_secs(render.timeout_seconds.asciidoc),
+            ),
+            templates,
+            storage,
+            pipeline: None,
         }
     }
 
-    pub async fn handle_message(&self, data: &[u8]) -> DocumentGenerationResponse {
+    /// Handler that persists documents through `DocumentPipeline` and responds
+    /// with artifact references instead of inline content.
+    pub fn with_pipeline(
+        pipeline: DocumentPipeline,
+        templates: Arc<TemplateRegistry>,
+        render: &RenderConfig,
+        render_permits: Arc<Semaphore>,
+    ) -> Self {
+        Self {
+            pipeline: Some(pipeline),
+            ..Self::new(templates, render, render_permits, None)
+        }
+    }
+
+    /// Handle one request; renders still running when `cancel` fires are
+    /// killed and reported as retryable Pandoc errors.
+    pub async fn handle_message(
+        &self,
+        message: &PubsubMessage,
+        cancel: &CancellationToken,
+    ) -> HandlerOutcome {
+        if let Some(pipeline) = &self.pipeline {
+            return self
+                .handle_pipeline_message(pipeline, message, cancel)
+                .await;
+        }
+
         // Parse the request
-        let request: DocumentGenerationRequest = match serde_json::from_slice(data) {
+        let mut request: DocumentGenerationRequest = match serde_json::from_slice(&message.data) {
             Ok(req) => req,
             Err(e) => {
-                error!("Failed to parse request: {}", e);
-                return DocumentGenerationResponse::error(
-                    "unknown".to_string(),
-                    format!("Invalid request format: {}", e),
-                );
+                let request_id = response_request_id([None, None], message);
+                error!(request_id = %request_id, "Failed to parse request: {}", e);
+                return HandlerOutcome::unparseable(request_id, message, e);
             }
         };
 
-        let request_id = uuid::Uuid::new_v4().to_string();
+        let request_id = response_request_id(
+            [
+                request.request_id.as_deref(),
+                request.correlation_id.as_deref(),
+            ],
+            message,
+        );
+        if request.correlation_id.is_none() {
+            request.correlation_id = attribute(message, "correlation_id");
+        }
+
+        // Convert an attached requirements file into the request data
+        if let Some(import) = request.import.take() {
+            match ingest::requirements::import(
+                request.specification_type.as_str(),
+                &request.data,
+                &import,
+                request.tenant_id.as_deref(),
+                self.storage.as_ref(),
+            )
+            .await
+            {
+                Ok(data) => request.data = data,
+                Err(e) => {
+                    error!(request_id = %request_id, "Failed to import requirements: {}", e);
+                    let mut outcome = HandlerOutcome::failure(request_id, e);
+                    outcome.response.correlation_id = request.correlation_id;
+                    return outcome;
+                }
+            }
+        }
 
+        let mut outcome = self.generate_documents(request_id, &request, cancel).await;
+        outcome.response.correlation_id = request.correlation_id;
+        outcome
+    }
+
+    async fn generate_documents(
+        &self,
+        request_id: String,
+        request: &DocumentGenerationRequest,
+        cancel: &CancellationToken,
+    ) -> HandlerOutcome {
         info!(
             request_id = %request_id,
             spec_type = ?request.specification_type,
@@ -46,44 +204,72 @@ impl MessageHandler {
         );
 
         // Generate the document content
-        let generator = match crate::generators::create_generator(&request.specification_type) {
+        let generator = match create_generator(&request.specification_type, &self.templates) {
             Ok(gen) => gen,
             Err(e) => {
                 error!("Failed to create generator: {}", e);
-                return DocumentGenerationResponse::error(request_id, e.to_string());
+                return HandlerOutcome::failure(request_id, e);
             }
         };
 
+        let spec_type = request.specification_type.as_str();
+        match self.templates.validate(spec_type, &request.data) {
+            Ok(violations) if violations.is_empty() => {}
+            Ok(violations) => {
+                warn!(
+                    request_id = %request_id,
+                    violations = violations.len(),
+                    "Request data failed schema validation"
+                );
+                let mut outcome = HandlerOutcome::failure(
+                    request_id,
+                    DocumentError::from_violations(&violations),
+                );
+                outcome.response.violations = violations;
+                return outcome;
+            }
+            Err(e) => return HandlerOutcome::failure(request_id, e),
+        }
+
         let markdown_content = match generator.generate(&request.data, &request.metadata).await {
             Ok(content) => content,
             Err(e) => {
                 error!("Failed to generate content: {}", e);
-                return DocumentGenerationResponse::error(request_id, e.to_string());
+                return HandlerOutcome::failure(request_id, e);
             }
         };
 
         // Render in requested formats
         let mut documents = Vec::new();
+        let mut last_error = None;
 
         for format in &request.output_formats {
             match self
-                .render_document(format, &markdown_content, &request.metadata)
+                .render_document(format, &markdown_content, request, cancel)
                 .await
             {
                 Ok(doc) => documents.push(doc),
                 Err(e) => {
                     warn!("Failed to render {} format: {}", format_name(format), e);
                     // Continue with other formats instead of failing completely
+                    last_error = Some(e);
                 }
             }
         }
 
         if documents.is_empty() {
             error!("Failed to generate any documents");
-            return DocumentGenerationResponse::error(
-                request_id,
-                "Failed to generate documents in any requested format".to_string(),
-            );
+            // Keep the underlying render error so a Pandoc crash stays retryable
+            let error = last_error.unwrap_or_else(|| {
+                DocumentError::GenerationFailed("no output formats requested".to_string())
+            });
+            return HandlerOutcome {
+                response: DocumentGenerationResponse::error(
+                    request_id,
+                    "Failed to generate documents in any requested format".to_string(),
+                ),
+                error: Some(error),
+            };
         }
 
         info!(
@@ -92,18 +278,120 @@ impl MessageHandler {
             "Successfully generated documents"
         );
 
-        DocumentGenerationResponse::success(request_id, documents)
+        HandlerOutcome::success(DocumentGenerationResponse::success(request_id, documents))
+    }
+
+    async fn handle_pipeline_message(
+        &self,
+        pipeline: &DocumentPipeline,
+        message: &PubsubMessage,
+        cancel: &CancellationToken,
+    ) -> HandlerOutcome {
+        let (request, request_id) = match parse_pipeline_request(message) {
+            Ok(parsed) => parsed,
+            Err(outcome) => return *outcome,
+        };
+        let correlation_id = request.correlation_id.clone();
+
+        info!(
+            request_id = %request_id,
+            document_type = %request.document_type,
+            formats = ?request.requested_formats,
+            "Processing pipeline document generation request"
+        );
+
+        let outcome = match pipeline.process(request, cancel).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                let e = pipeline::classify(e);
+                error!(request_id = %request_id, error = %e, "Pipeline processing failed");
+                let mut outcome = HandlerOutcome::failure(request_id, e);
+                outcome.response.correlation_id = correlation_id;
+                return outcome;
+            }
+        };
+
+        let document = outcome.document;
+        if document.status != "completed" {
+            let message = document
+                .error_message
+                .unwrap_or_else(|| format!("Document ended in status {}", document.status));
+            // A render killed by shutdown is retried; the redelivery gets a new record
+            let error = if cancel.is_cancelled() {
+                DocumentError::PandocError(message.clone())
+            } else {
+                outcome
+                    .error
+                    .unwrap_or_else(|| DocumentError::GenerationFailed(message.clone()))
+            };
+            let mut outcome = HandlerOutcome {
+                response: DocumentGenerationResponse::error(request_id, message),
+                error: Some(error),
+            };
+            outcome.response.document_id = Some(document.id);
+            outcome.response.correlation_id = correlation_id;
+            return outcome;
+        }
+
+        let artifacts: Vec<ArtifactReference> = outcome
+            .artifacts
+            .into_iter()
+            .map(|a| ArtifactReference {
+                artifact_id: a.id,
+                format: a.format,
+                filename: a.file_name,
+                gcs_path: a.gcs_path,
+                mime_type: a.content_type,
+                size_bytes: a.file_size,
+                sha256_checksum: a.sha256_checksum,
+            })
+            .collect();
+
+        info!(
+            request_id = %request_id,
+            document_id = document.id,
+            artifact_count = artifacts.len(),
+            "Successfully stored documents"
+        );
+
+        let mut response = DocumentGenerationResponse::stored(request_id, document.id, artifacts);
+        response.correlation_id = correlation_id;
+        HandlerOutcome::success(response)
+    }
+
+    /// PDF layout profile: the request's, else the one its manifest entry
+    /// names, else the default.
+    fn pdf_layout(&self, request: &DocumentGenerationRequest) -> Result<PdfLayout> {
+        let template_layout = self
+            .templates
+            .entry(request.specification_type.as_str())
+            .ok()
+            .and_then(|entry| entry.layout);
+        self.templates
+            .pdf_layout(request.metadata.layout.as_deref().or(template_layout.as_deref()))
     }
 
     async fn render_document(
         &self,
         format: &DocumentFormat,
         markdown_content: &str,
-        metadata: &crate::models::DocumentMetadata,
+        request: &DocumentGenerationRequest,
+        cancel: &CancellationToken,
     ) -> Result<GeneratedDocument> {
+        let metadata = &request.metadata;
+        let requirements = || {
+            RequirementSet::from_data(
+                request.specification_type.as_str(),
+                &request.data,
+                metadata,
+            )
+        };
         let (content_bytes, mime_type, extension) = match format {
             DocumentFormat::PDF => {
-                let bytes = self.pdf_renderer.render(markdown_content, metadata).await?;
+                let bytes = self
+                    .pdf_renderer
+                    .render(markdown_content, metadata, &self.pdf_layout(request)?, cancel)
+                    .await?;
                 (bytes, "application/pdf", "pdf")
             }
             DocumentFormat::Markdown => {
@@ -114,12 +402,82 @@ impl MessageHandler {
                 (bytes, "text/markdown", "md")
             }
             DocumentFormat::HTML => {
+                let theme = self
+                    .templates
+                    .theme(metadata.theme.as_deref(), request.tenant_id.as_deref())?;
                 let bytes = self
                     .html_renderer
-                    .render(markdown_content, metadata)
+                    .render(markdown_content, metadata, theme.as_ref(), cancel)
                     .await?;
                 (bytes, "text/html", "html")
             }
+            DocumentFormat::DOCX => {
+                let bytes = self
+                    .docx_renderer
+                    .render(markdown_content, metadata, cancel)
+                    .await?;
+                (
+                    bytes,
+                    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+                    "docx",
+                )
+            }
+            DocumentFormat::ODT => {
+                let bytes = self
+                    .odt_renderer
+                    .render(markdown_content, metadata, cancel)
+                    .await?;
+                (bytes, "application/vnd.oasis.opendocument.text", "odt")
+            }
+            DocumentFormat::EPUB => {
+                let bytes = self
+                    .epub_renderer
+                    .render(markdown_content, metadata, cancel)
+                    .await?;
+                (bytes, "application/epub+zip", "epub")
+            }
+            DocumentFormat::AsciiDoc => {
+                let bytes = self
+                    .asciidoc_renderer
+                    .render(markdown_content, metadata, cancel)
+                    .await?;
+                (bytes, "text/asciidoc", "adoc")
+            }
+            DocumentFormat::LaTeX => {
+                let bytes = self
+                    .pdf_renderer
+                    .render_latex(markdown_content, metadata, &self.pdf_layout(request)?, cancel)
+                    .await?;
+                (bytes, "application/x-tex", "tex")
+            }
+            DocumentFormat::LaTeXBundle => {
+                let bytes = self
+                    .pdf_renderer
+                    .render_latex_bundle(
+                        markdown_content,
+                        metadata,
+                        &self.pdf_layout(request)?,
+                        cancel,
+                    )
+                    .await?;
+                (bytes, "application/zip", "zip")
+            }
+            DocumentFormat::RequirementsJSON => {
+                (requirements()?.to_json()?, "application/json", "requirements.json")
+            }
+            DocumentFormat::RequirementsCSV => {
+                (requirements()?.to_csv()?, "text/csv", "requirements.csv")
+            }
+            DocumentFormat::RequirementsXLSX => (
+                requirements()?.to_xlsx()?,
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+                "requirements.xlsx",
+            ),
+            DocumentFormat::ReqIF => (
+                reqif::to_reqif(&requirements()?, metadata)?,
+                "application/xml",
+                "reqif",
+            ),
         };
 
         let content_base64 = general_purpose::STANDARD.encode(&content_bytes);
@@ -142,10 +500,285 @@ impl MessageHandler {
     }
 }
 
+/// Pipeline request in `message` and the ID to respond with. The request's
+/// correlation ID defaults to the publisher's `correlation_id` attribute.
+fn parse_pipeline_request(
+    message: &PubsubMessage,
+) -> std::result::Result<(pipeline::DocumentGenerationRequest, String), Box<HandlerOutcome>> {
+    let mut request: pipeline::DocumentGenerationRequest =
+        match serde_json::from_slice(&message.data) {
+            Ok(req) => req,
+            Err(e) => {
+                let request_id = response_request_id([None, None], message);
+                error!(request_id = %request_id, "Failed to parse pipeline request: {}", e);
+                return Err(Box::new(HandlerOutcome::unparseable(
+                    request_id, message, e,
+                )));
+            }
+        };
+
+    // Pipeline requests carry no request_id of their own
+    let request_id = response_request_id([None, request.correlation_id.as_deref()], message);
+    if request.correlation_id.is_none() {
+        request.correlation_id = attribute(message, "correlation_id");
+    }
+    Ok((request, request_id))
+}
+
+/// Non-empty message attribute.
+fn attribute(message: &PubsubMessage, key: &str) -> Option<String> {
+    message
+        .attributes
+        .get(key)
+        .filter(|value| !value.is_empty())
+        .cloned()
+}
+
+/// ID to respond with, picked the same way in both modes: the body's
+/// `request_id`, then its `correlation_id`, then the publisher's
+/// `request_id` and `correlation_id` attributes, then the Pub/Sub message ID.
+fn response_request_id(body_ids: [Option<&str>; 2], message: &PubsubMessage) -> String {
+    let attributes = ["request_id", "correlation_id"]
+        .iter()
+        .filter_map(|key| message.attributes.get(*key).map(String::as_str));
+    body_ids
+        .into_iter()
+        .flatten()
+        .chain(attributes)
+        .chain(std::iter::once(message.message_id.as_str()))
+        .find(|id| !id.is_empty())
+        .map_or_else(|| uuid::Uuid::new_v4().to_string(), str::to_string)
+}
+
 fn format_name(format: &DocumentFormat) -> &str {
     match format {
         DocumentFormat::PDF => "PDF",
         DocumentFormat::Markdown => "Markdown",
         DocumentFormat::HTML => "HTML",
+        DocumentFormat::DOCX => "DOCX",
+        DocumentFormat::ODT => "ODT",
+        DocumentFormat::EPUB => "EPUB",
+        DocumentFormat::AsciiDoc => "AsciiDoc",
+        DocumentFormat::LaTeX => "LaTeX",
+        DocumentFormat::LaTeXBundle => "LaTeX bundle",
+        DocumentFormat::RequirementsJSON => "requirements JSON",
+        DocumentFormat::RequirementsCSV => "requirements CSV",
+        DocumentFormat::RequirementsXLSX => "requirements XLSX",
+        DocumentFormat::ReqIF => "ReqIF",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::TemplateRegistry;
+    use serde_json::json;
+
+    fn handler() -> MessageHandler {
+        let render: RenderConfig = serde_json::from_value(json!({
+            "max_concurrent_renders": 1,
+            "timeout_seconds": {
+                "pdf": 60, "html": 60, "docx": 60, "odt": 60, "epub": 60,
+                "asciidoc": 60, "latex": 60, "latex_bundle": 60
+            }
+        }))
+        .unwrap();
+        let templates = TemplateRegistry::load("./templates").expect("templates compile");
+        MessageHandler::new(
+            Arc::new(templates),
+            &render,
+            Arc::new(Semaphore::new(1)),
+            None,
+        )
+    }
+
+    fn message(body: serde_json::Value, attributes: &[(&str, &str)]) -> PubsubMessage {
+        PubsubMessage {
+            data: serde_json::to_vec(&body).unwrap(),
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            message_id: "4711".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn inline_request(correlation_id: Option<&str>) -> serde_json::Value {
+        json!({
+            "correlation_id": correlation_id,
+            "specification_type": "iso29148_software_requirements",
+            "output_formats": ["Markdown"],
+            "data": { "requirements": [] },
+            "metadata": {
+                "title": "Software Requirements Specification",
+                "project_name": "QXProveIt Platform",
+                "version": "1.0.0",
+                "author": "Engineering Team",
+                "organization": "mcx Services, LLC"
+            }
+        })
+    }
+
+    fn pipeline_request(correlation_id: Option<&str>) -> serde_json::Value {
+        json!({
+            "tenant_id": "7f1c2d3e-0000-4000-8000-000000000001",
+            "project_id": 42,
+            "template_id": null,
+            "correlation_id": correlation_id,
+            "title": "Software Requirements Specification",
+            "document_type": "iso29148_software_requirements",
+            "requested_formats": ["pdf"],
+            "input_params": { "requirements": [] },
+            "requested_by": 1001
+        })
+    }
+
+    #[tokio::test]
+    async fn inline_mode_echoes_the_correlation_id_attribute() {
+        let handler = handler();
+        let cancel = CancellationToken::new();
+        let attributes = [("correlation_id", "order-service/7781")];
+
+        let unparseable = PubsubMessage {
+            data: b"{not json".to_vec(),
+            ..message(json!({}), &attributes)
+        };
+        let outcome = handler.handle_message(&unparseable, &cancel).await;
+        assert!(outcome.error.is_some());
+        assert_eq!(outcome.response.request_id, "order-service/7781");
+        assert_eq!(
+            outcome.response.correlation_id.as_deref(),
+            Some("order-service/7781")
+        );
+
+        let parsed = message(inline_request(None), &attributes);
+        let outcome = handler.handle_message(&parsed, &cancel).await;
+        assert_eq!(
+            outcome.response.correlation_id.as_deref(),
+            Some("order-service/7781")
+        );
+
+        let own = message(inline_request(Some("billing/12")), &attributes);
+        let outcome = handler.handle_message(&own, &cancel).await;
+        assert_eq!(outcome.response.request_id, "billing/12");
+        assert_eq!(
+            outcome.response.correlation_id.as_deref(),
+            Some("billing/12")
+        );
+    }
+
+    #[test]
+    fn pipeline_mode_keeps_non_uuid_correlation_ids() {
+        let (request, request_id) =
+            parse_pipeline_request(&message(pipeline_request(Some("order-service/7781")), &[]))
+                .unwrap();
+        assert_eq!(
+            request.correlation_id.as_deref(),
+            Some("order-service/7781")
+        );
+        assert_eq!(request_id, "order-service/7781");
+
+        let attributes = [("correlation_id", "order-service/7781")];
+        let (request, request_id) =
+            parse_pipeline_request(&message(pipeline_request(None), &attributes)).unwrap();
+        assert_eq!(
+            request.correlation_id.as_deref(),
+            Some("order-service/7781")
+        );
+        assert_eq!(request_id, "order-service/7781");
+
+        let (request, request_id) =
+            parse_pipeline_request(&message(pipeline_request(None), &[])).unwrap();
+        assert_eq!(request.correlation_id, None);
+        assert_eq!(request_id, "4711");
+    }
+
+    #[test]
+    fn pipeline_mode_echoes_the_correlation_id_attribute_on_unparseable_bodies() {
+        let attributes = [("correlation_id", "order-service/7781")];
+        let outcome =
+            parse_pipeline_request(&message(json!({ "title": 7 }), &attributes)).unwrap_err();
+        assert!(matches!(
+            outcome.error,
+            Some(DocumentError::SerializationError(_))
+        ));
+        assert_eq!(outcome.response.request_id, "order-service/7781");
+        assert_eq!(
+            outcome.response.correlation_id.as_deref(),
+            Some("order-service/7781")
+        );
+    }
+
+    #[tokio::test]
+    async fn render_document_routes_the_pandoc_formats() {
+        let cases = [
+            (
+                DocumentFormat::ODT,
+                "ODT",
+                "application/vnd.oasis.opendocument.text",
+                "odt",
+            ),
+            (DocumentFormat::EPUB, "EPUB", "application/epub+zip", "epub"),
+            (
+                DocumentFormat::AsciiDoc,
+                "AsciiDoc",
+                "text/asciidoc",
+                "adoc",
+            ),
+        ];
+        for (format, name, _, _) in &cases {
+            assert_eq!(format_name(format), *name);
+        }
+
+        if !crate::renderers::pandoc_available() {
+            eprintln!("pandoc not installed; skipping");
+            return;
+        }
+        let handler = handler();
+        let request: DocumentGenerationRequest =
+            serde_json::from_value(inline_request(None)).unwrap();
+        for (format, name, mime_type, extension) in cases {
+            let document = handler
+                .render_document(&format, "# Scope\n", &request, &CancellationToken::new())
+                .await
+                .unwrap();
+
+            assert_eq!(format_name(&document.format), name);
+            assert_eq!(document.mime_type, mime_type);
+            assert_eq!(
+                document.filename,
+                format!("software-requirements-specification-v1.0.0.{}", extension)
+            );
+            assert!(document.size_bytes > 0);
+        }
+    }
+
+    #[tokio::test]
+    async fn both_modes_pick_the_same_ids_from_the_attributes() {
+        let attributes = [
+            ("request_id", "gateway-31"),
+            ("correlation_id", "order-service/7781"),
+        ];
+
+        let inline = handler()
+            .handle_message(
+                &message(inline_request(None), &attributes),
+                &CancellationToken::new(),
+            )
+            .await;
+        let (pipeline, pipeline_id) =
+            parse_pipeline_request(&message(pipeline_request(None), &attributes)).unwrap();
+
+        assert_eq!(inline.response.request_id, "gateway-31");
+        assert_eq!(pipeline_id, "gateway-31");
+        assert_eq!(
+            inline.response.correlation_id.as_deref(),
+            Some("order-service/7781")
+        );
+        assert_eq!(
+            pipeline.correlation_id.as_deref(),
+            Some("order-service/7781")
+        );
     }
 }
@@ -1,7 +1,9 @@
 // document-generation-service/src/pubsub/mod.rs
 
+mod delivery;
 mod handler;
 mod publisher;
 
+pub use delivery::DeliveryTracker;
 pub use handler::MessageHandler;
 pub use publisher::Publisher;
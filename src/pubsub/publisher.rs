@@ -1,8 +1,12 @@
 // document-generation-service/src/pubsub/publisher.rs
 
+use crate::error::{DocumentError, ErrorResponse, Result};
 use crate::models::DocumentGenerationResponse;
+use base64::{engine::general_purpose, Engine as _};
+use google_cloud_googleapis::pubsub::v1::PubsubMessage;
 use google_cloud_pubsub::client::{Client, ClientConfig};
 use google_cloud_pubsub::publisher::Publisher as PubSubPublisher;
+use std::collections::HashMap;
 use tracing::{error, info};
 
 pub struct Publisher {
@@ -11,7 +15,10 @@ pub struct Publisher {
 }
 
 impl Publisher {
-    pub async fn new(project_id: &str, topic_name: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub async fn new(
+        project_id: &str,
+        topic_name: &str,
+    ) -> std::result::Result<Self, Box<dyn std::error::Error>> {
         let config = ClientConfig::default().with_auth().await?;
         let client = Client::new(config).await?;
 
@@ -30,30 +37,30 @@ impl Publisher {
         })
     }
 
-    pub async fn publish_response(&self, response: &DocumentGenerationResponse) {
-        let json_data = match serde_json::to_vec(response) {
-            Ok(data) => data,
-            Err(e) => {
-                error!("Failed to serialize response: {}", e);
-                return;
-            }
-        };
+    pub async fn publish_response(&self, response: &DocumentGenerationResponse) -> Result<()> {
+        let json_data = serde_json::to_vec(response).map_err(|e| {
+            error!("Failed to serialize response: {}", e);
+            e
+        })?;
+
+        let mut attributes: HashMap<String, String> = vec![
+            ("request_id".to_string(), response.request_id.clone()),
+            ("status".to_string(), response.status.clone()),
+        ]
+        .into_iter()
+        .collect();
+        if let Some(correlation_id) = &response.correlation_id {
+            attributes.insert("correlation_id".to_string(), correlation_id.clone());
+        }
 
         // Create PubsubMessage using googleapis
-        let message = google_cloud_googleapis::pubsub::v1::PubsubMessage {
+        let message = PubsubMessage {
             data: json_data,
-            attributes: vec![
-                ("request_id".to_string(), response.request_id.clone()),
-                ("status".to_string(), response.status.clone()),
-            ]
-            .into_iter()
-            .collect(),
+            attributes,
             ..Default::default()
         };
 
-        let awaiter = self.publisher.publish(message).await;
-
-        match awaiter.get().await {
+        match self.publish(message).await {
             Ok(message_id) => {
                 info!(
                     request_id = %response.request_id,
@@ -61,6 +68,7 @@ impl Publisher {
                     topic = %self.topic_name,
                     "Response published successfully"
                 );
+                Ok(())
             }
             Err(e) => {
                 error!(
@@ -68,7 +76,50 @@ impl Publisher {
                     error = %e,
                     "Failed to publish response"
                 );
+                Err(e)
             }
         }
     }
+
+    /// Republish an undeliverable request inside an envelope that also holds
+    /// the full error; `attributes` carry the short form.
+    pub async fn publish_dead_letter(
+        &self,
+        data: &[u8],
+        error: &ErrorResponse,
+        attributes: HashMap<String, String>,
+    ) -> Result<()> {
+        // Requests that reach dead-lettering parsed as JSON; keep anything
+        // else byte-for-byte as base64
+        let request = serde_json::from_slice::<serde_json::Value>(data)
+            .unwrap_or_else(|_| serde_json::Value::String(general_purpose::STANDARD.encode(data)));
+        let envelope = serde_json::json!({
+            "request": request,
+            "error": error,
+        });
+        let message = PubsubMessage {
+            data: serde_json::to_vec(&envelope)?,
+            attributes,
+            ..Default::default()
+        };
+
+        let message_id = self.publish(message).await?;
+
+        info!(
+            message_id = %message_id,
+            topic = %self.topic_name,
+            "Message published to dead-letter topic"
+        );
+
+        Ok(())
+    }
+
+    async fn publish(&self, message: PubsubMessage) -> Result<String> {
+        let awaiter = self.publisher.publish(message).await;
+
+        awaiter
+            .get()
+            .await
+            .map_err(|e| DocumentError::PubSubError(e.to_string()))
+    }
 }
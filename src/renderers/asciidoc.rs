@@ -0,0 +1,140 @@
+// document-generation-service/src/renderers/asciidoc.rs
+
+use crate::error::Result;
+use crate::models::DocumentMetadata;
+use crate::renderers::process;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+pub struct AsciiDocRenderer {
+    permits: Arc<Semaphore>,
+    /// Time a Pandoc run may take before it is killed.
+    timeout: Duration,
+}
+
+impl AsciiDocRenderer {
+    pub fn new(permits: Arc<Semaphore>, timeout: Duration) -> Self {
+        Self { permits, timeout }
+    }
+
+    pub async fn render(
+        &self,
+        markdown_content: &str,
+        metadata: &DocumentMetadata,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<u8>> {
+        info!(title = %metadata.title, "Rendering AsciiDoc document");
+
+        // Create temporary file
+        let mut md_file = NamedTempFile::new()?;
+
+        // Write markdown to temp file
+        use std::io::Write;
+        md_file.write_all(markdown_content.as_bytes())?;
+        md_file.flush()?;
+
+        debug!("Markdown written to: {:?}", md_file.path());
+
+        // Build Pandoc command for the AsciiDoc body; the document header is added below
+        let mut cmd = Command::new("pandoc");
+        cmd.arg(md_file.path())
+            .arg("--from=markdown+yaml_metadata_block")
+            .arg("--to=asciidoctor")
+            .arg("--wrap=none");
+
+        let output = process::render(&self.permits, &mut cmd, self.timeout, cancel).await?;
+
+        let body = String::from_utf8_lossy(&output);
+        let full_content = format!("{}\n{}", document_header(metadata), body);
+
+        info!(
+            title = %metadata.title,
+            size_kb = full_content.len() / 1024,
+            "AsciiDoc generated successfully"
+        );
+
+        Ok(full_content.into_bytes())
+    }
+}
+
+/// AsciiDoc document header: the title followed by attributes from the
+/// metadata, so Antora pages can reference them as `{project-name}`,
+/// `{classification}` and so on.
+fn document_header(metadata: &DocumentMetadata) -> String {
+    let mut header = format!(
+        "= {}\n:doctype: book\n:toc:\n:toclevels: 3\n:sectnums:\n",
+        metadata.title
+    );
+
+    let attributes = [
+        ("author", Some(&metadata.author)),
+        ("revnumber", Some(&metadata.version)),
+        (
+            "revdate",
+            Some(&metadata.generated_date.format("%Y-%m-%d").to_string()),
+        ),
+        ("project-name", Some(&metadata.project_name)),
+        ("organization", Some(&metadata.organization)),
+        ("classification", metadata.classification.as_ref()),
+        (
+            "distribution-statement",
+            metadata.distribution_statement.as_ref(),
+        ),
+    ];
+    for (name, value) in attributes {
+        // Attribute values end at the line break
+        if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+            header.push_str(&format!(":{}: {}\n", name, value.replace('\n', " ")));
+        }
+    }
+
+    header
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderers::pandoc_available;
+
+    #[tokio::test]
+    async fn render_starts_with_the_document_header() {
+        if !pandoc_available() {
+            eprintln!("pandoc not installed; skipping");
+            return;
+        }
+        let metadata: DocumentMetadata = serde_json::from_value(serde_json::json!({
+            "title": "System Specification",
+            "project_name": "Project",
+            "version": "1.0",
+            "author": "Author",
+            "organization": "Organization",
+            "classification": "CUI",
+            "distribution_statement": null
+        }))
+        .unwrap();
+        let renderer = AsciiDocRenderer::new(Arc::new(Semaphore::new(1)), Duration::from_secs(60));
+
+        let bytes = renderer
+            .render(
+                "# Scope\n\nThe system.\n",
+                &metadata,
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+
+        let document = String::from_utf8(bytes).unwrap();
+        assert!(
+            document.starts_with("= System Specification\n"),
+            "{document}"
+        );
+        assert!(document.contains("\n:classification: CUI\n"));
+        assert!(!document.contains(":distribution-statement:"));
+        assert!(document.contains("== Scope"));
+    }
+}
@@ -0,0 +1,456 @@
+// document-generation-service/src/renderers/docx.rs
+
+use crate::error::{DocumentError, Result};
+use crate::marking::Marking;
+use crate::models::DocumentMetadata;
+use crate::renderers::process;
+use std::io::{Cursor, Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::fs;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const DOCUMENT_PART: &str = "word/document.xml";
+const DOCUMENT_RELS_PART: &str = "word/_rels/document.xml.rels";
+const CONTENT_TYPES_PART: &str = "[Content_Types].xml";
+
+const HEADER_PART: &str = "classificationHeader.xml";
+const FOOTER_PART: &str = "classificationFooter.xml";
+const HEADER_REL_ID: &str = "rIdClassificationHeader";
+const FOOTER_REL_ID: &str = "rIdClassificationFooter";
+
+const PAGE_BREAK: &str = r#"<w:p><w:r><w:br w:type="page"/></w:r></w:p>"#;
+
+/// Marks the content control Pandoc writes the table of contents into.
+const TOC_GALLERY: &str = r#"w:docPartGallery w:val="Table of Contents""#;
+
+pub struct DocxRenderer {
+    permits: Arc<Semaphore>,
+    /// Time a Pandoc run may take before it is killed.
+    timeout: Duration,
+    /// Word document whose styles, page setup and headers Pandoc copies.
+    reference_doc: Option<PathBuf>,
+}
+
+impl DocxRenderer {
+    pub fn new(permits: Arc<Semaphore>, reference_doc: Option<PathBuf>, timeout: Duration) -> Self {
+        Self {
+            permits,
+            reference_doc,
+            timeout,
+        }
+    }
+
+    pub async fn render(
+        &self,
+        markdown_content: &str,
+        metadata: &DocumentMetadata,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<u8>> {
+        info!(title = %metadata.title, "Rendering DOCX document");
+
+        // Create temporary files
+        let mut md_file = NamedTempFile::new()?;
+        let docx_file = NamedTempFile::new()?;
+
+        // Write markdown to temp file
+        md_file.write_all(markdown_content.as_bytes())?;
+        md_file.flush()?;
+
+        debug!("Markdown written to: {:?}", md_file.path());
+
+        // Build Pandoc command for DOCX; the title block comes from metadata
+        let mut cmd = Command::new("pandoc");
+        cmd.arg(md_file.path())
+            .arg("-o")
+            .arg(docx_file.path())
+            .arg("--from=markdown+yaml_metadata_block")
+            .arg("--to=docx")
+            .arg("--toc")
+            .arg("--toc-depth=3")
+            .arg("-M")
+            .arg(format!("title={}", metadata.title))
+            .arg("-M")
+            .arg(format!(
+                "subtitle={} — Version {}",
+                metadata.project_name, metadata.version
+            ))
+            .arg("-M")
+            .arg(format!("author={}", metadata.author))
+            .arg("-M")
+            .arg(format!("author={}", metadata.organization))
+            .arg("-M")
+            .arg(format!(
+                "date={}",
+                metadata.generated_date.format("%B %d, %Y")
+            ));
+
+        // Kept as a custom document property; the title page shows it too
+        if let Some(statement) = &metadata.distribution_statement {
+            cmd.arg("-M")
+                .arg(format!("distribution_statement={}", statement));
+        }
+
+        if let Some(reference_doc) = &self.reference_doc {
+            cmd.arg(format!("--reference-doc={}", reference_doc.display()));
+        }
+
+        process::render(&self.permits, &mut cmd, self.timeout, cancel).await?;
+
+        // Read DOCX bytes and finish the title page and classification markings
+        let docx_bytes = fs::read(docx_file.path()).await?;
+        let marking = metadata.classification.as_deref().map(Marking::new);
+        let docx_bytes = finish_docx(
+            &docx_bytes,
+            marking.as_ref(),
+            metadata.distribution_statement.as_deref(),
+        )?;
+
+        info!(
+            title = %metadata.title,
+            size_kb = docx_bytes.len() / 1024,
+            "DOCX generated successfully"
+        );
+
+        Ok(docx_bytes)
+    }
+}
+
+/// Rewrite the package Pandoc produced: end the title page, with the
+/// distribution statement when one is given, before the table of contents
+/// and, when a classification is given, replace the page header and footer
+/// on every page with its banner.
+fn finish_docx(docx: &[u8], marking: Option<&Marking>, statement: Option<&str>) -> Result<Vec<u8>> {
+    let title_page_end = match statement {
+        Some(statement) => format!("{}{}", statement_paragraph(statement), PAGE_BREAK),
+        None => PAGE_BREAK.to_string(),
+    };
+    let mut archive = ZipArchive::new(Cursor::new(docx)).map_err(package_error)?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::with_capacity(docx.len())));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(package_error)?;
+        let name = file.name().to_string();
+        if ![DOCUMENT_PART, DOCUMENT_RELS_PART, CONTENT_TYPES_PART].contains(&name.as_str()) {
+            writer.raw_copy_file(file).map_err(package_error)?;
+            continue;
+        }
+
+        let mut xml = String::new();
+        file.read_to_string(&mut xml)?;
+        let xml = match (name.as_str(), marking) {
+            (DOCUMENT_PART, None) => end_title_page(&xml, &title_page_end),
+            (DOCUMENT_PART, Some(_)) => {
+                reference_classification_parts(&end_title_page(&xml, &title_page_end))
+            }
+            (DOCUMENT_RELS_PART, Some(_)) => insert_before(
+                &xml,
+                "</Relationships>",
+                &format!(
+                    r#"<Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/header" Target="{}"/><Relationship Id="{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/footer" Target="{}"/>"#,
+                    HEADER_REL_ID, HEADER_PART, FOOTER_REL_ID, FOOTER_PART
+                ),
+            ),
+            (CONTENT_TYPES_PART, Some(_)) => insert_before(
+                &xml,
+                "</Types>",
+                &format!(
+                    r#"<Override PartName="/word/{}" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.header+xml"/><Override PartName="/word/{}" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.footer+xml"/>"#,
+                    HEADER_PART, FOOTER_PART
+                ),
+            ),
+            _ => xml,
+        };
+
+        writer.start_file(name, options).map_err(package_error)?;
+        writer.write_all(xml.as_bytes())?;
+    }
+
+    if let Some(marking) = marking {
+        writer
+            .start_file(format!("word/{}", HEADER_PART), options)
+            .map_err(package_error)?;
+        writer.write_all(marking_part("hdr", marking).as_bytes())?;
+        writer
+            .start_file(format!("word/{}", FOOTER_PART), options)
+            .map_err(package_error)?;
+        writer.write_all(marking_part("ftr", marking).as_bytes())?;
+    }
+
+    Ok(writer.finish().map_err(package_error)?.into_inner())
+}
+
+/// Close the title page with `content` (the distribution statement and a
+/// page break) just before the table of contents.
+fn end_title_page(xml: &str, content: &str) -> String {
+    let toc = xml
+        .find(TOC_GALLERY)
+        .and_then(|gallery| element_starts(&xml[..gallery], "w:sdt").pop());
+    match toc {
+        Some(i) => format!("{}{}{}", &xml[..i], content, &xml[i..]),
+        None => xml.to_string(),
+    }
+}
+
+/// Point the final section at the classification header and footer in place
+/// of any it inherited from the reference document.
+fn reference_classification_parts(xml: &str) -> String {
+    let Some((start, end)) = body_section(xml) else {
+        return xml.to_string();
+    };
+    let open_len = xml[start..].find('>').map_or(0, |i| i + 1);
+
+    let references: String = ["default", "first", "even"]
+        .iter()
+        .map(|kind| {
+            format!(
+                r#"<w:headerReference w:type="{kind}" r:id="{}"/><w:footerReference w:type="{kind}" r:id="{}"/>"#,
+                HEADER_REL_ID, FOOTER_REL_ID
+            )
+        })
+        .collect();
+
+    let open_tag = &xml[start..start + open_len];
+    let (open_tag, body) = match open_tag.strip_suffix("/>") {
+        // <w:sectPr/> has no children to keep
+        Some(attributes) => (format!("{}>", attributes.trim_end()), String::new()),
+        None => {
+            // A tracked change, always the last child, keeps its references
+            let children = &xml[start + open_len..end - "</w:sectPr>".len()];
+            let change = element_starts(children, "w:sectPrChange")
+                .first()
+                .copied()
+                .unwrap_or(children.len());
+            let (own, change) = children.split_at(change);
+            (
+                open_tag.to_string(),
+                strip_elements(own, &["<w:headerReference", "<w:footerReference"]) + change,
+            )
+        }
+    };
+
+    format!(
+        "{}{}{}{}</w:sectPr>{}",
+        &xml[..start],
+        open_tag,
+        references,
+        body,
+        &xml[end..]
+    )
+}
+
+/// Byte range of the body's own `<w:sectPr>`, the last element before
+/// `</w:body>`. A tracked change nests an earlier `<w:sectPr>` inside it,
+/// so start and end tags are paired by depth.
+fn body_section(xml: &str) -> Option<(usize, usize)> {
+    let body = xml[..xml.rfind("</w:body>")?].trim_end();
+    if !body.ends_with("</w:sectPr>") {
+        let start = element_starts(body, "w:sectPr").pop()?;
+        return body[start..]
+            .find('>')
+            .filter(|i| start + i + 1 == body.len() && body[..start + i].ends_with('/'))
+            .map(|_| (start, body.len()));
+    }
+
+    // Walk back from the final end tag to the start tag that opens it
+    let mut tags: Vec<(usize, i32)> = element_starts(body, "w:sectPr")
+        .into_iter()
+        .filter(|&i| {
+            body[i..]
+                .find('>')
+                .is_some_and(|end| !body[..i + end].ends_with('/'))
+        })
+        .map(|i| (i, -1))
+        .chain(body.match_indices("</w:sectPr>").map(|(i, _)| (i, 1)))
+        .collect();
+    tags.sort_unstable();
+    let mut depth = 0;
+    for (index, step) in tags.into_iter().rev() {
+        depth += step;
+        if depth == 0 {
+            return Some((index, body.len()));
+        }
+    }
+    None
+}
+
+/// Offsets of the start tags of `name`, leaving out longer names that begin
+/// with it (`w:sdt` does not match `<w:sdtPr>`, nor `w:sectPr` `<w:sectPrChange>`).
+fn element_starts(xml: &str, name: &str) -> Vec<usize> {
+    let tag = format!("<{}", name);
+    xml.match_indices(&tag)
+        .map(|(i, _)| i)
+        .filter(|&i| {
+            matches!(
+                xml[i + tag.len()..].chars().next(),
+                Some('>' | '/' | ' ' | '\t' | '\r' | '\n')
+            )
+        })
+        .collect()
+}
+
+/// Remove self-closing elements starting with any of `prefixes`.
+fn strip_elements(xml: &str, prefixes: &[&str]) -> String {
+    let mut out = String::with_capacity(xml.len());
+    let mut rest = xml;
+    while let Some(start) = prefixes.iter().filter_map(|p| rest.find(p)).min() {
+        out.push_str(&rest[..start]);
+        rest = rest[start..]
+            .find("/>")
+            .map_or("", |end| &rest[start + end + 2..]);
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Insert `content` before the first occurrence of `marker`, if any.
+fn insert_before(xml: &str, marker: &str, content: &str) -> String {
+    match xml.find(marker) {
+        Some(i) => format!("{}{}{}", &xml[..i], content, &xml[i..]),
+        None => xml.to_string(),
+    }
+}
+
+/// A header (`hdr`) or footer (`ftr`) part with the centred, bold banner in
+/// the marking's colors, as in PDF and HTML.
+fn marking_part(element: &str, marking: &Marking) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><w:{element} xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:p><w:pPr><w:shd w:val="clear" w:color="auto" w:fill="{}"/><w:jc w:val="center"/></w:pPr><w:r><w:rPr><w:b/><w:color w:val="{}"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r></w:p></w:{element}>"#,
+        marking.background,
+        marking.foreground,
+        escape_xml(&marking.text)
+    )
+}
+
+/// The distribution statement as a centred, small paragraph for the title
+/// page.
+fn statement_paragraph(statement: &str) -> String {
+    format!(
+        r#"<w:p><w:pPr><w:spacing w:before="480"/><w:jc w:val="center"/></w:pPr><w:r><w:rPr><w:sz w:val="18"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r></w:p>"#,
+        escape_xml(statement)
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn package_error(e: zip::result::ZipError) -> DocumentError {
+    DocumentError::GenerationFailed(format!("DOCX package: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOC: &str = r#"<w:sdt><w:sdtPr><w:docPartObj><w:docPartGallery w:val="Table of Contents" /><w:docPartUnique /></w:docPartObj></w:sdtPr><w:sdtContent><w:p><w:r><w:t>Contents</w:t></w:r></w:p></w:sdtContent></w:sdt>"#;
+
+    /// A document.xml like Pandoc's: a title block, a citation content
+    /// control before the table of contents, and a final section that
+    /// carries a tracked change with its own nested `<w:sectPr>`.
+    fn document_xml() -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><w:body><w:p><w:pPr><w:pStyle w:val="Title" /></w:pPr><w:r><w:t>System Specification</w:t></w:r></w:p><w:sdt><w:sdtPr><w:citation /></w:sdtPr><w:sdtContent><w:p /></w:sdtContent></w:sdt>{TOC}<w:p><w:r><w:t>Body</w:t></w:r></w:p><w:sectPr w:rsidR="00A1"><w:headerReference w:type="default" r:id="rId9" /><w:pgSz w:w="12240" w:h="15840" /><w:sectPrChange w:id="1" w:author="Reviewer"><w:sectPr><w:footerReference w:type="default" r:id="rId8" /></w:sectPr></w:sectPrChange></w:sectPr></w:body></w:document>"#
+        )
+    }
+
+    fn package(document: &str) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in [
+            (CONTENT_TYPES_PART, "<Types></Types>"),
+            (DOCUMENT_RELS_PART, "<Relationships></Relationships>"),
+            (DOCUMENT_PART, document),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn part(docx: &[u8], name: &str) -> String {
+        let mut archive = ZipArchive::new(Cursor::new(docx)).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        xml
+    }
+
+    #[test]
+    fn finish_docx_ends_the_title_page_before_the_table_of_contents() {
+        let docx = finish_docx(
+            &package(&document_xml()),
+            None,
+            Some("DISTRIBUTION A. Approved for public release."),
+        )
+        .unwrap();
+
+        let xml = part(&docx, DOCUMENT_PART);
+        let statement = xml.find("DISTRIBUTION A.").unwrap();
+        let page_break = xml.find(PAGE_BREAK).unwrap();
+        assert!(xml.find("<w:citation />").unwrap() < statement);
+        assert!(statement < page_break);
+        assert_eq!(&xml[page_break + PAGE_BREAK.len()..][..TOC.len()], TOC);
+        // No classification: the sections keep their headers
+        assert!(xml.contains(r#"r:id="rId9""#));
+        assert!(ZipArchive::new(Cursor::new(&docx[..]))
+            .unwrap()
+            .by_name(&format!("word/{}", HEADER_PART))
+            .is_err());
+    }
+
+    #[test]
+    fn finish_docx_marks_the_body_section_with_the_banner() {
+        let marking = Marking::new("secret//noforn");
+
+        let docx = finish_docx(&package(&document_xml()), Some(&marking), None).unwrap();
+
+        let xml = part(&docx, DOCUMENT_PART);
+        let section = &xml[xml.find(r#"<w:sectPr w:rsidR="00A1">"#).unwrap()..];
+        assert!(section.starts_with(&format!(
+            r#"<w:sectPr w:rsidR="00A1"><w:headerReference w:type="default" r:id="{}"/>"#,
+            HEADER_REL_ID
+        )));
+        assert!(!section.contains("rId9"));
+        assert!(section.contains(r#"<w:pgSz w:w="12240" w:h="15840" />"#));
+        // The tracked change keeps its own section properties
+        assert!(section.contains(
+            r#"<w:sectPrChange w:id="1" w:author="Reviewer"><w:sectPr><w:footerReference w:type="default" r:id="rId8" /></w:sectPr></w:sectPrChange></w:sectPr></w:body>"#
+        ));
+
+        let header = part(&docx, &format!("word/{}", HEADER_PART));
+        assert!(header.contains(">SECRET//NOFORN</w:t>"));
+        assert!(header.contains(&format!(r#"w:fill="{}""#, marking.background)));
+        assert!(header.contains(&format!(r#"<w:color w:val="{}"/>"#, marking.foreground)));
+        assert!(part(&docx, DOCUMENT_RELS_PART).contains(FOOTER_REL_ID));
+        assert!(part(&docx, CONTENT_TYPES_PART).contains(FOOTER_PART));
+    }
+
+    #[test]
+    fn reference_classification_parts_opens_an_empty_section() {
+        let xml = r#"<w:body><w:p /><w:sectPr /></w:body>"#;
+
+        let xml = reference_classification_parts(xml);
+
+        assert!(xml.starts_with(&format!(
+            r#"<w:body><w:p /><w:sectPr><w:headerReference w:type="default" r:id="{}"/>"#,
+            HEADER_REL_ID
+        )));
+        assert!(xml.ends_with("</w:sectPr></w:body>"));
+    }
+}
@@ -0,0 +1,144 @@
+// document-generation-service/src/renderers/epub.rs
+
+use crate::error::Result;
+use crate::models::DocumentMetadata;
+use crate::renderers::process;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::fs;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+pub struct EpubRenderer {
+    permits: Arc<Semaphore>,
+    /// Time a Pandoc run may take before it is killed.
+    timeout: Duration,
+    /// Cover image shown by e-readers; none when unset.
+    cover_image: Option<PathBuf>,
+}
+
+impl EpubRenderer {
+    pub fn new(permits: Arc<Semaphore>, cover_image: Option<PathBuf>, timeout: Duration) -> Self {
+        Self {
+            permits,
+            cover_image,
+            timeout,
+        }
+    }
+
+    pub async fn render(
+        &self,
+        markdown_content: &str,
+        metadata: &DocumentMetadata,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<u8>> {
+        info!(title = %metadata.title, "Rendering EPUB document");
+
+        // Create temporary files
+        let mut md_file = NamedTempFile::new()?;
+        let epub_file = NamedTempFile::new()?;
+
+        // Write markdown to temp file
+        use std::io::Write;
+        md_file.write_all(markdown_content.as_bytes())?;
+        md_file.flush()?;
+
+        debug!("Markdown written to: {:?}", md_file.path());
+
+        // Build Pandoc command for EPUB 3; metadata becomes the package's Dublin Core
+        let mut cmd = Command::new("pandoc");
+        cmd.arg(md_file.path())
+            .arg("-o")
+            .arg(epub_file.path())
+            .arg("--from=markdown+yaml_metadata_block")
+            .arg("--to=epub3")
+            .arg("--toc")
+            .arg("--toc-depth=3")
+            .arg("-M")
+            .arg(format!("title={}", metadata.title))
+            .arg("-M")
+            .arg(format!(
+                "subtitle={} — Version {}",
+                metadata.project_name, metadata.version
+            ))
+            .arg("-M")
+            .arg(format!("creator={}", metadata.author))
+            .arg("-M")
+            .arg(format!("publisher={}", metadata.organization))
+            .arg("-M")
+            .arg(format!(
+                "date={}",
+                metadata.generated_date.format("%Y-%m-%d")
+            ));
+
+        if let Some(statement) = &metadata.distribution_statement {
+            cmd.arg("-M").arg(format!("rights={}", statement));
+        }
+
+        if let Some(cover_image) = &self.cover_image {
+            cmd.arg(format!("--epub-cover-image={}", cover_image.display()));
+        }
+
+        process::render(&self.permits, &mut cmd, self.timeout, cancel).await?;
+
+        // Read EPUB bytes
+        let epub_bytes = fs::read(epub_file.path()).await?;
+
+        info!(
+            title = %metadata.title,
+            size_kb = epub_bytes.len() / 1024,
+            "EPUB generated successfully"
+        );
+
+        Ok(epub_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderers::pandoc_available;
+    use std::io::{Cursor, Read};
+    use zip::ZipArchive;
+
+    #[tokio::test]
+    async fn render_writes_an_epub_package() {
+        if !pandoc_available() {
+            eprintln!("pandoc not installed; skipping");
+            return;
+        }
+        let metadata: DocumentMetadata = serde_json::from_value(serde_json::json!({
+            "title": "System Specification",
+            "project_name": "Project",
+            "version": "1.0",
+            "author": "Author",
+            "organization": "Organization",
+            "classification": null,
+            "distribution_statement": "Distribution A"
+        }))
+        .unwrap();
+        let renderer =
+            EpubRenderer::new(Arc::new(Semaphore::new(1)), None, Duration::from_secs(60));
+
+        let bytes = renderer
+            .render(
+                "# Scope\n\nThe system.\n",
+                &metadata,
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+
+        // The EPUB signature: a stored `mimetype` entry first in the zip
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut entry = archive.by_index(0).unwrap();
+        assert_eq!(entry.name(), "mimetype");
+        let mut mimetype = String::new();
+        entry.read_to_string(&mut mimetype).unwrap();
+        assert_eq!(mimetype, "application/epub+zip");
+    }
+}
@@ -1,25 +1,43 @@
 // document-generation-service/src/renderers/html.rs
 
 use crate::error::Result;
+use crate::generators::Theme;
+use crate::marking::Marking;
 use crate::models::DocumentMetadata;
-use std::process::Command;
+use crate::renderers::process;
+use std::sync::Arc;
+use std::time::Duration;
 use tempfile::NamedTempFile;
 use tokio::fs;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
 
-pub struct HtmlRenderer;
+pub struct HtmlRenderer {
+    permits: Arc<Semaphore>,
+    /// Time a Pandoc run may take before it is killed.
+    timeout: Duration,
+}
 
 impl HtmlRenderer {
-    pub fn new() -> Self {
-        Self
+    pub fn new(permits: Arc<Semaphore>, timeout: Duration) -> Self {
+        Self { permits, timeout }
     }
 
     pub async fn render(
         &self,
         markdown_content: &str,
         metadata: &DocumentMetadata,
+        theme: Option<&Theme>,
+        cancel: &CancellationToken,
     ) -> Result<Vec<u8>> {
-        info!(title = %metadata.title, "Rendering HTML document");
+        info!(
+            title = %metadata.title,
+            theme = theme.map(|t| t.name.as_str()).unwrap_or("none"),
+            theme_version = theme.map(|t| t.version.as_str()).unwrap_or("-"),
+            "Rendering HTML document"
+        );
 
         // Create temporary files
         let mut md_file = NamedTempFile::new()?;
@@ -42,7 +60,6 @@ impl HtmlRenderer {
             .arg("--standalone")
             .arg("--toc")
             .arg("--toc-depth=3")
-            .arg("--css=https://cdnjs.cloudflare.com/ajax/libs/github-markdown-css/5.1.0/github-markdown.min.css")
             .arg("--self-contained")
             .arg("-V")
             .arg(format!("title={}", metadata.title))
@@ -50,15 +67,13 @@ impl HtmlRenderer {
             .arg(format!("author={}", metadata.author))
             .arg("-V")
             .arg(format!("date={}", metadata.generated_date.format("%B %d, %Y")));
-
-        debug!("Running Pandoc: {:?}", cmd);
-
-        let output = cmd.output()?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::error::DocumentError::PandocError(stderr.to_string()).into());
+        // Bundled stylesheet, embedded into the output; nothing is fetched
+        if let Some(theme) = theme {
+            cmd.arg(format!("--css={}", theme.stylesheet.display()));
         }
+        add_markings(&mut cmd, metadata);
+
+        process::render(&self.permits, &mut cmd, self.timeout, cancel).await?;
 
         // Read HTML bytes
         let html_bytes = fs::read(html_file.path()).await?;
@@ -72,3 +87,121 @@ impl HtmlRenderer {
         Ok(html_bytes)
     }
 }
+
+/// Banners fixed to the top and bottom of the viewport, and so repeated on
+/// every printed page, with room kept for them around the text.
+const MARKING_STYLE: &str = r#"<style>
+.classification-banner { position: fixed; left: 0; right: 0; z-index: 10; padding: 2px 0; font: bold 14px/1.4 sans-serif; text-align: center; -webkit-print-color-adjust: exact; print-color-adjust: exact; }
+.classification-banner.top { top: 0; }
+.classification-banner.bottom { bottom: 0; }
+body:has(> .classification-banner) { padding-top: 3em; padding-bottom: 3em; }
+.distribution-statement { font-size: 0.85em; text-align: center; }
+</style>"#;
+
+/// Classification banners at the top and bottom, and the distribution
+/// statement under the top banner and at the end of the document, passed
+/// through Pandoc's include variables.
+fn add_markings(cmd: &mut Command, metadata: &DocumentMetadata) {
+    let marking = metadata.classification.as_deref().map(Marking::new);
+    let statement = metadata.distribution_statement.as_deref();
+    if marking.is_none() && statement.is_none() {
+        return;
+    }
+
+    let banner = |position: &str| {
+        marking.as_ref().map_or(String::new(), |marking| {
+            format!(
+                r#"<div class="classification-banner {}" style="background: #{}; color: #{};">{}</div>"#,
+                position,
+                marking.background,
+                marking.foreground,
+                escape_html(&marking.text)
+            )
+        })
+    };
+    let statement = statement.map_or(String::new(), |statement| {
+        format!(
+            r#"<p class="distribution-statement">{}</p>"#,
+            escape_html(statement)
+        )
+    });
+
+    cmd.arg("-V")
+        .arg(format!("header-includes={}", MARKING_STYLE))
+        .arg("-V")
+        .arg(format!("include-before={}{}", banner("top"), statement))
+        .arg("-V")
+        .arg(format!("include-after={}{}", statement, banner("bottom")));
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marking_args(classification: Option<&str>, statement: Option<&str>) -> Vec<String> {
+        let metadata: DocumentMetadata = serde_json::from_value(serde_json::json!({
+            "title": "System Specification",
+            "project_name": "Project",
+            "version": "1.0",
+            "author": "Author",
+            "organization": "Organization",
+            "classification": classification,
+            "distribution_statement": statement
+        }))
+        .unwrap();
+        let mut cmd = Command::new("pandoc");
+        add_markings(&mut cmd, &metadata);
+        cmd.as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn add_markings_puts_banners_and_statement_around_the_body() {
+        let args = marking_args(Some("secret//noforn"), Some("Distribution D <DoD only>"));
+
+        let banner = |position: &str| {
+            format!(
+                r#"<div class="classification-banner {}" style="background: #C8102E; color: #FFFFFF;">SECRET//NOFORN</div>"#,
+                position
+            )
+        };
+        let statement = r#"<p class="distribution-statement">Distribution D &lt;DoD only&gt;</p>"#;
+        assert_eq!(
+            args,
+            [
+                "-V".to_string(),
+                format!("header-includes={}", MARKING_STYLE),
+                "-V".to_string(),
+                format!("include-before={}{}", banner("top"), statement),
+                "-V".to_string(),
+                format!("include-after={}{}", statement, banner("bottom")),
+            ]
+        );
+    }
+
+    #[test]
+    fn add_markings_leaves_out_what_the_metadata_lacks() {
+        assert!(marking_args(None, None).is_empty());
+
+        let args = marking_args(None, Some("Distribution A"));
+        let statement = r#"<p class="distribution-statement">Distribution A</p>"#;
+        assert_eq!(args[3], format!("include-before={}", statement));
+        assert_eq!(args[5], format!("include-after={}", statement));
+
+        let args = marking_args(Some("CUI"), None);
+        assert!(args[3].starts_with("include-before=<div class=\"classification-banner top\""));
+        assert!(args[3].ends_with(">CUI</div>"));
+        assert!(!args
+            .iter()
+            .any(|arg| arg.contains("distribution-statement\">")));
+    }
+}
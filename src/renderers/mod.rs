@@ -1,9 +1,21 @@
 // document-generation-service/src/renderers/mod.rs
 
+mod asciidoc;
+mod docx;
+mod epub;
 mod html;
 mod markdown;
+mod odt;
 mod pdf;
+mod process;
 
+pub use asciidoc::AsciiDocRenderer;
+pub use docx::DocxRenderer;
+pub use epub::EpubRenderer;
 pub use html::HtmlRenderer;
 pub use markdown::MarkdownRenderer;
+pub use odt::OdtRenderer;
 pub use pdf::PdfRenderer;
+
+#[cfg(test)]
+pub(crate) use process::pandoc_available;
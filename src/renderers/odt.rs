@@ -0,0 +1,137 @@
+// document-generation-service/src/renderers/odt.rs
+
+use crate::error::Result;
+use crate::models::DocumentMetadata;
+use crate::renderers::process;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tokio::fs;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+pub struct OdtRenderer {
+    permits: Arc<Semaphore>,
+    /// Time a Pandoc run may take before it is killed.
+    timeout: Duration,
+    /// OpenDocument file whose styles and page setup Pandoc copies.
+    reference_doc: Option<PathBuf>,
+}
+
+impl OdtRenderer {
+    pub fn new(permits: Arc<Semaphore>, reference_doc: Option<PathBuf>, timeout: Duration) -> Self {
+        Self {
+            permits,
+            reference_doc,
+            timeout,
+        }
+    }
+
+    pub async fn render(
+        &self,
+        markdown_content: &str,
+        metadata: &DocumentMetadata,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<u8>> {
+        info!(title = %metadata.title, "Rendering ODT document");
+
+        // Create temporary files
+        let mut md_file = NamedTempFile::new()?;
+        let odt_file = NamedTempFile::new()?;
+
+        // Write markdown to temp file
+        use std::io::Write;
+        md_file.write_all(markdown_content.as_bytes())?;
+        md_file.flush()?;
+
+        debug!("Markdown written to: {:?}", md_file.path());
+
+        // Build Pandoc command for ODT; the title block comes from metadata
+        let mut cmd = Command::new("pandoc");
+        cmd.arg(md_file.path())
+            .arg("-o")
+            .arg(odt_file.path())
+            .arg("--from=markdown+yaml_metadata_block")
+            .arg("--to=odt")
+            .arg("--toc")
+            .arg("--toc-depth=3")
+            .arg("-M")
+            .arg(format!("title={}", metadata.title))
+            .arg("-M")
+            .arg(format!(
+                "subtitle={} — Version {}",
+                metadata.project_name, metadata.version
+            ))
+            .arg("-M")
+            .arg(format!("author={}", metadata.author))
+            .arg("-M")
+            .arg(format!(
+                "date={}",
+                metadata.generated_date.format("%B %d, %Y")
+            ));
+
+        if let Some(reference_doc) = &self.reference_doc {
+            cmd.arg(format!("--reference-doc={}", reference_doc.display()));
+        }
+
+        process::render(&self.permits, &mut cmd, self.timeout, cancel).await?;
+
+        // Read ODT bytes
+        let odt_bytes = fs::read(odt_file.path()).await?;
+
+        info!(
+            title = %metadata.title,
+            size_kb = odt_bytes.len() / 1024,
+            "ODT generated successfully"
+        );
+
+        Ok(odt_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderers::pandoc_available;
+    use std::io::{Cursor, Read};
+    use zip::ZipArchive;
+
+    #[tokio::test]
+    async fn render_writes_an_odt_package() {
+        if !pandoc_available() {
+            eprintln!("pandoc not installed; skipping");
+            return;
+        }
+        let metadata: DocumentMetadata = serde_json::from_value(serde_json::json!({
+            "title": "System Specification",
+            "project_name": "Project",
+            "version": "1.0",
+            "author": "Author",
+            "organization": "Organization",
+            "classification": null,
+            "distribution_statement": "Distribution A"
+        }))
+        .unwrap();
+        let renderer = OdtRenderer::new(Arc::new(Semaphore::new(1)), None, Duration::from_secs(60));
+
+        let bytes = renderer
+            .render(
+                "# Scope\n\nThe system.\n",
+                &metadata,
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+
+        // The ODT signature: a stored `mimetype` entry first in the zip
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut entry = archive.by_index(0).unwrap();
+        assert_eq!(entry.name(), "mimetype");
+        let mut mimetype = String::new();
+        entry.read_to_string(&mut mimetype).unwrap();
+        assert_eq!(mimetype, "application/vnd.oasis.opendocument.text");
+    }
+}
@@ -1,33 +1,80 @@
 // document-generation-service/src/renderers/pdf.rs
 
-use crate::error::Result;
+use crate::config::RenderTimeouts;
+use crate::error::{DocumentError, Result};
+use crate::generators::PdfLayout;
+use crate::marking::Marking;
 use crate::models::DocumentMetadata;
-use std::process::Command;
+use crate::renderers::process;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tempfile::NamedTempFile;
 use tokio::fs;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
 
-pub struct PdfRenderer;
+/// LaTeX source file name inside the bundle.
+const BUNDLE_TEX: &str = "document.tex";
+
+/// Directory inside the bundle holding the images the source references.
+const BUNDLE_MEDIA_DIR: &str = "media";
+
+/// Directory inside the bundle holding the font files.
+const BUNDLE_FONT_DIR: &str = "fonts";
+
+/// Extensions of the font files copied into the bundle.
+const FONT_EXTENSIONS: &[&str] = &["otf", "ttf", "ttc", "pfb"];
+
+/// fontspec and unicode-math commands Pandoc emits to select a font by name.
+const FONT_COMMANDS: &[&str] = &[
+    r"\setmainfont",
+    r"\setsansfont",
+    r"\setmonofont",
+    r"\setmathfont",
+];
+
+/// Words in a font file name that name the regular face rather than a
+/// variant such as light or condensed.
+const FACE_WORDS: &[&str] = &[
+    "regular", "book", "roman", "normal", "bold", "italic", "oblique",
+];
+
+pub struct PdfRenderer {
+    permits: Arc<Semaphore>,
+    /// Limits for the PDF, LaTeX source and LaTeX bundle outputs.
+    timeouts: RenderTimeouts,
+}
 
 impl PdfRenderer {
-    pub fn new() -> Self {
-        Self
+    /// `permits` is shared by every renderer that spawns Pandoc, bounding the
+    /// number of concurrent subprocesses.
+    pub fn new(permits: Arc<Semaphore>, timeouts: RenderTimeouts) -> Self {
+        Self { permits, timeouts }
     }
 
     pub async fn render(
         &self,
         markdown_content: &str,
         metadata: &DocumentMetadata,
+        layout: &PdfLayout,
+        cancel: &CancellationToken,
     ) -> Result<Vec<u8>> {
-        info!(title = %metadata.title, "Rendering PDF document");
+        info!(title = %metadata.title, layout = %layout.name, "Rendering PDF document");
 
         // Create temporary files
         let mut md_file = NamedTempFile::new()?;
         let pdf_file = NamedTempFile::new()?;
 
         // Write markdown to temp file
-        use std::io::Write;
-        md_file.write_all(markdown_content.as_bytes())?;
+        md_file.write_all(prepare_markdown(markdown_content, layout).as_bytes())?;
         md_file.flush()?;
 
         debug!("Markdown written to: {:?}", md_file.path());
@@ -37,40 +84,12 @@ impl PdfRenderer {
         cmd.arg(md_file.path())
             .arg("-o")
             .arg(pdf_file.path())
-            .arg("--from=markdown+yaml_metadata_block+hard_line_breaks")
             .arg("--to=pdf")
-            .arg("--pdf-engine=xelatex")
-            .arg("--toc")
-            .arg("--toc-depth=3")
-            .arg("--number-sections")
-            .arg("-V")
-            .arg("geometry:margin=1in")
-            .arg("-V")
-            .arg("fontsize=11pt")
-            .arg("-V")
-            .arg("documentclass=article")
-            .arg("-V")
-            .arg(format!("title={}", metadata.title))
-            .arg("-V")
-            .arg(format!("author={}", metadata.author))
-            .arg("-V")
-            .arg(format!("date={}", metadata.generated_date.format("%B %d, %Y")));
-
-        // Add classification if present (for military docs)
-        if let Some(classification) = &metadata.classification {
-            cmd.arg("-V")
-                .arg(format!("header-includes=\\markboth{{{}}}{{{}}}",
-                    classification, classification));
-        }
-
-        debug!("Running Pandoc: {:?}", cmd);
+            .arg("--pdf-engine=xelatex");
+        add_latex_options(&mut cmd, metadata, layout);
 
-        let output = cmd.output()?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(crate::error::DocumentError::PandocError(stderr.to_string()).into());
-        }
+        let timeout = Duration::from_secs(self.timeouts.pdf);
+        process::render(&self.permits, &mut cmd, timeout, cancel).await?;
 
         // Read PDF bytes
         let pdf_bytes = fs::read(pdf_file.path()).await?;
@@ -83,4 +102,571 @@ impl PdfRenderer {
 
         Ok(pdf_bytes)
     }
+
+    /// The standalone LaTeX source `render` hands to XeLaTeX.
+    pub async fn render_latex(
+        &self,
+        markdown_content: &str,
+        metadata: &DocumentMetadata,
+        layout: &PdfLayout,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<u8>> {
+        info!(title = %metadata.title, layout = %layout.name, "Rendering LaTeX source");
+
+        // Create temporary file
+        let mut md_file = NamedTempFile::new()?;
+
+        // Write markdown to temp file
+        md_file.write_all(prepare_markdown(markdown_content, layout).as_bytes())?;
+        md_file.flush()?;
+
+        debug!("Markdown written to: {:?}", md_file.path());
+
+        // Build Pandoc command; LaTeX goes to stdout
+        let mut cmd = Command::new("pandoc");
+        cmd.arg(md_file.path())
+            .arg("--to=latex")
+            .arg("--standalone");
+        add_latex_options(&mut cmd, metadata, layout);
+
+        let timeout = Duration::from_secs(self.timeouts.latex);
+        let latex = process::render(&self.permits, &mut cmd, timeout, cancel).await?;
+
+        info!(
+            title = %metadata.title,
+            size_kb = latex.len() / 1024,
+            "LaTeX generated successfully"
+        );
+
+        Ok(latex)
+    }
+
+    /// A zip of the LaTeX source with the images it references under
+    /// `media/` and the font files XeLaTeX loaded under `fonts/`, so the PDF
+    /// can be rebuilt offline.
+    pub async fn render_latex_bundle(
+        &self,
+        markdown_content: &str,
+        metadata: &DocumentMetadata,
+        layout: &PdfLayout,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<u8>> {
+        info!(title = %metadata.title, layout = %layout.name, "Rendering LaTeX bundle");
+
+        // Work in a temporary directory so extracted media get relative paths
+        let work_dir = tempfile::tempdir()?;
+        fs::write(
+            work_dir.path().join("document.md"),
+            prepare_markdown(markdown_content, layout).as_bytes(),
+        )
+        .await?;
+
+        // Build Pandoc command
+        let mut cmd = Command::new("pandoc");
+        cmd.current_dir(work_dir.path())
+            .arg("document.md")
+            .arg("-o")
+            .arg(BUNDLE_TEX)
+            .arg("--to=latex")
+            .arg("--standalone")
+            .arg(format!("--extract-media={}", BUNDLE_MEDIA_DIR));
+        add_latex_options(&mut cmd, metadata, layout);
+
+        // Typeset once with the recorder on to learn which font files are used
+        let mut xelatex = Command::new("xelatex");
+        xelatex
+            .current_dir(work_dir.path())
+            .arg("-interaction=nonstopmode")
+            .arg("-halt-on-error")
+            .arg("-recorder")
+            .arg(BUNDLE_TEX);
+
+        // Both steps share one time limit
+        let deadline = Instant::now() + Duration::from_secs(self.timeouts.latex_bundle);
+        for cmd in [&mut cmd, &mut xelatex] {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            process::render(&self.permits, cmd, remaining, cancel).await?;
+        }
+
+        let recorded = fs::read_to_string(work_dir.path().join("document.fls")).await?;
+        let fonts = font_files(&recorded);
+        let tex = fs::read_to_string(work_dir.path().join(BUNDLE_TEX)).await?;
+        let bundle = write_bundle(work_dir.path(), &bundle_tex(&tex, &fonts), &fonts)?;
+
+        info!(
+            title = %metadata.title,
+            size_kb = bundle.len() / 1024,
+            "LaTeX bundle generated successfully"
+        );
+
+        Ok(bundle)
+    }
+}
+
+/// Source, layout, variables and classification marking shared by the PDF
+/// and the LaTeX source outputs.
+fn add_latex_options(cmd: &mut Command, metadata: &DocumentMetadata, layout: &PdfLayout) {
+    cmd.arg("--from=markdown+yaml_metadata_block+hard_line_breaks")
+        .arg("--toc")
+        .arg(format!("--toc-depth={}", layout.toc_depth))
+        .arg("-V")
+        .arg(format!("geometry:margin={}", layout.margin))
+        .arg("-V")
+        .arg(format!("papersize={}", layout.paper_size.as_str()))
+        .arg("-V")
+        .arg(format!("fontsize={}", layout.font_size))
+        .arg("-V")
+        .arg(format!("documentclass={}", layout.document_class))
+        .arg("-V")
+        .arg(format!("title={}", metadata.title))
+        .arg("-V")
+        .arg(format!("author={}", metadata.author))
+        .arg("-V")
+        .arg(format!("date={}", title_date(metadata)));
+    if layout.number_sections {
+        cmd.arg("--number-sections");
+    }
+    if let Some(font) = &layout.main_font {
+        cmd.arg("-V").arg(format!("mainfont={}", font));
+    }
+    if let Some(spacing) = layout.line_spacing {
+        cmd.arg("-V").arg(format!("linestretch={}", spacing));
+    }
+    if layout.landscape_appendices {
+        cmd.arg("-V").arg(r"header-includes=\usepackage{pdflscape}");
+    }
+
+    // Banner markings and the distribution statement in the page footer
+    let marking = metadata.classification.as_deref().map(Marking::new);
+    let statement = metadata.distribution_statement.as_deref();
+    if marking.is_some() || statement.is_some() {
+        cmd.arg("-V").arg(format!(
+            "header-includes={}",
+            page_markings(marking.as_ref(), statement)
+        ));
+    }
+}
+
+/// The title block's date line, followed by the distribution statement so
+/// the statement is on the title page in every document class, including
+/// the separate title page of `report` and the KOMA classes.
+fn title_date(metadata: &DocumentMetadata) -> String {
+    let date = metadata.generated_date.format("%B %d, %Y").to_string();
+    match &metadata.distribution_statement {
+        Some(statement) => format!(r"{}\\[2ex]{{\small {}}}", date, escape_latex(statement)),
+        None => date,
+    }
+}
+
+/// LaTeX preamble giving every page a banner in the level's colors at the
+/// top and bottom, with the distribution statement and page number between
+/// the bottom banner and the text. The `plain` and `empty` styles get the
+/// markings too, so chapter openings and the separate title page of
+/// `report` and the KOMA classes are marked.
+fn page_markings(marking: Option<&Marking>, statement: Option<&str>) -> String {
+    let mut preamble = vec![
+        r"\usepackage{fancyhdr}".to_string(),
+        r"\usepackage{xcolor}".to_string(),
+    ];
+    let mut header = String::new();
+    let mut footer = String::new();
+    if let Some(statement) = statement {
+        footer.push_str(&format!(r"{{\scriptsize {}\par}}\vspace{{2pt}}", escape_latex(statement)));
+    }
+    footer.push_str(r"\thepage");
+    if let Some(marking) = marking {
+        preamble.push(format!(r"\definecolor{{markingbg}}{{HTML}}{{{}}}", marking.background));
+        preamble.push(format!(r"\definecolor{{markingfg}}{{HTML}}{{{}}}", marking.foreground));
+        preamble.push(format!(
+            r"\newcommand{{\classificationbanner}}{{\colorbox{{markingbg}}{{\parbox{{\dimexpr\textwidth-2\fboxsep\relax}}{{\centering\color{{markingfg}}\bfseries\strut {}}}}}}}",
+            escape_latex(&marking.text)
+        ));
+        header.push_str(r"\classificationbanner");
+        footer.push_str(r"\par\vspace{2pt}\classificationbanner");
+    }
+    preamble.push(format!(
+        r"\newcommand{{\markingstyle}}{{\fancyhf{{}}\renewcommand{{\headrulewidth}}{{0pt}}\fancyhead[C]{{{}}}\fancyfoot[C]{{\parbox[t]{{\textwidth}}{{\centering {}}}}}}}",
+        header, footer
+    ));
+    preamble.extend([
+        r"\pagestyle{fancy}\markingstyle".to_string(),
+        r"\fancypagestyle{plain}{\markingstyle}".to_string(),
+        r"\fancypagestyle{empty}{\markingstyle}".to_string(),
+        r"\setlength{\headheight}{20pt}".to_string(),
+        r"\setlength{\footskip}{24pt}".to_string(),
+    ]);
+    preamble.join("\n")
+}
+
+/// Escape LaTeX special characters in marking and statement text.
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\textbackslash{}"),
+            '~' => escaped.push_str(r"\textasciitilde{}"),
+            '^' => escaped.push_str(r"\textasciicircum{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The Markdown as typeset under `layout`: with landscape appendices, every
+/// section from the first level 1 or 2 heading starting with "Appendix" to
+/// the end of the document goes on landscape pages.
+fn prepare_markdown<'a>(markdown: &'a str, layout: &PdfLayout) -> Cow<'a, str> {
+    if !layout.landscape_appendices {
+        return Cow::Borrowed(markdown);
+    }
+
+    let mut in_fence = false;
+    let mut offset = 0;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && is_appendix_heading(line) {
+            return Cow::Owned(format!(
+                "{}\n\n```{{=latex}}\n\\begin{{landscape}}\n```\n\n{}\n\n```{{=latex}}\n\\end{{landscape}}\n```\n",
+                &markdown[..offset],
+                &markdown[offset..]
+            ));
+        }
+        offset += line.len();
+    }
+    Cow::Borrowed(markdown)
+}
+
+fn is_appendix_heading(line: &str) -> bool {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let Some(text) = line[level..].strip_prefix(' ') else {
+        return false;
+    };
+    (1..=2).contains(&level) && text.trim_start().to_lowercase().starts_with("appendix")
+}
+
+/// Font files listed as inputs in a XeLaTeX `-recorder` file list.
+fn font_files(recorded: &str) -> Vec<PathBuf> {
+    let work_dir = recorded
+        .lines()
+        .find_map(|line| line.strip_prefix("PWD "))
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    let mut fonts: Vec<PathBuf> = recorded
+        .lines()
+        .filter_map(|line| line.strip_prefix("INPUT "))
+        .map(|path| work_dir.join(path))
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| FONT_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        })
+        .collect();
+    fonts.sort();
+    fonts.dedup();
+    fonts
+}
+
+/// The LaTeX source with every font Pandoc selected by name
+/// (`\setmainfont{DejaVu Serif}`) loaded by file name from `fonts/` instead,
+/// so the bundle builds without the fonts installed. Fonts with no files in
+/// the bundle are left as they are.
+fn bundle_tex(tex: &str, fonts: &[PathBuf]) -> String {
+    let names: Vec<String> = fonts
+        .iter()
+        .filter_map(|font| font.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+    tex.split_inclusive('\n')
+        .map(|line| bundled_font_command(line, &names).unwrap_or_else(|| line.to_string()))
+        .collect()
+}
+
+/// `line` rewritten to load its font from the bundle, if it is a font
+/// selection the bundle has files for.
+fn bundled_font_command(line: &str, fonts: &[String]) -> Option<String> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    let command = FONT_COMMANDS.iter().find(|c| trimmed.starts_with(**c))?;
+    let rest = &trimmed[command.len()..];
+    let (options, rest) = match rest.strip_prefix('[') {
+        Some(rest) => rest.split_once(']')?,
+        None => ("", rest),
+    };
+    let (family, tail) = rest.strip_prefix('{')?.split_once('}')?;
+    let faces = FontFaces::find(family, fonts)?;
+
+    let mut settings = vec![format!("Path={}/", BUNDLE_FONT_DIR)];
+    for (key, file) in [
+        ("BoldFont", faces.bold),
+        ("ItalicFont", faces.italic),
+        ("BoldItalicFont", faces.bold_italic),
+    ] {
+        if let Some(file) = file {
+            settings.push(format!("{}={}", key, file));
+        }
+    }
+    if !options.trim().is_empty() {
+        settings.push(options.trim().to_string());
+    }
+    Some(format!(
+        "{}{}[{}]{{{}}}{}",
+        indent,
+        command,
+        settings.join(","),
+        faces.upright,
+        tail
+    ))
+}
+
+/// Bundled font files of one family, by face.
+struct FontFaces {
+    upright: String,
+    bold: Option<String>,
+    italic: Option<String>,
+    bold_italic: Option<String>,
+}
+
+impl FontFaces {
+    /// Match `family` against the file names, ignoring case, spaces and
+    /// punctuation (`DejaVu Serif` -> `DejaVuSerif-Bold.ttf`). Each face
+    /// takes the file with the fewest other words in its name, so `Bold`
+    /// wins over `SemiBold` and `Regular` over `Light`.
+    fn find(family: &str, fonts: &[String]) -> Option<Self> {
+        let family = normalize(family);
+        if family.is_empty() {
+            return None;
+        }
+        let mut faces: [Option<(usize, &String)>; 4] = [None; 4];
+        for name in fonts {
+            let stem = Path::new(name)
+                .file_stem()
+                .map(|stem| normalize(&stem.to_string_lossy()))
+                .unwrap_or_default();
+            let Some(style) = stem.strip_prefix(&family) else {
+                continue;
+            };
+            let bold = style.contains("bold");
+            let italic = style.contains("italic") || style.contains("oblique");
+            let extra = FACE_WORDS
+                .iter()
+                .fold(style.to_string(), |style, word| style.replace(word, ""))
+                .len();
+            let face = &mut faces[usize::from(bold) + 2 * usize::from(italic)];
+            if face.is_none_or(|(best, _)| extra < best) {
+                *face = Some((extra, name));
+            }
+        }
+        let [upright, bold, italic, bold_italic] =
+            faces.map(|face| face.map(|(_, name)| name.clone()));
+        Some(Self {
+            upright: upright?,
+            bold,
+            italic,
+            bold_italic,
+        })
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Zip the LaTeX source `tex`, the extracted media and `fonts` (flattened
+/// into `fonts/`).
+fn write_bundle(work_dir: &Path, tex: &str, fonts: &[PathBuf]) -> Result<Vec<u8>> {
+    let mut files = Vec::new();
+
+    let mut pending = vec![work_dir.join(BUNDLE_MEDIA_DIR)];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(work_dir) {
+                files.push((relative.to_string_lossy().replace('\\', "/"), path.clone()));
+            }
+        }
+    }
+
+    for font in fonts {
+        if let Some(name) = font.file_name() {
+            files.push((
+                format!("{}/{}", BUNDLE_FONT_DIR, name.to_string_lossy()),
+                font.clone(),
+            ));
+        }
+    }
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    writer
+        .start_file(BUNDLE_TEX, options)
+        .map_err(bundle_error)?;
+    writer.write_all(tex.as_bytes())?;
+    let mut added = HashSet::from([BUNDLE_TEX.to_string()]);
+    for (name, path) in files {
+        if !added.insert(name.clone()) {
+            continue;
+        }
+        writer.start_file(name, options).map_err(bundle_error)?;
+        writer.write_all(&std::fs::read(&path)?)?;
+    }
+
+    Ok(writer.finish().map_err(bundle_error)?.into_inner())
+}
+
+fn bundle_error(e: zip::result::ZipError) -> DocumentError {
+    DocumentError::GenerationFailed(format!("LaTeX bundle: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    #[test]
+    fn bundle_loads_fonts_from_the_bundle() {
+        let work_dir = tempfile::tempdir().unwrap();
+        let system_fonts = tempfile::tempdir().unwrap();
+        let fonts: Vec<PathBuf> = [
+            "DejaVuSerif.ttf",
+            "DejaVuSerif-Bold.ttf",
+            "DejaVuSerif-Italic.ttf",
+            "DejaVuSerif-BoldItalic.ttf",
+            "DejaVuSerifCondensed-Bold.ttf",
+            "lmmono10-regular.otf",
+        ]
+        .iter()
+        .map(|name| {
+            let path = system_fonts.path().join(name);
+            std::fs::write(&path, name).unwrap();
+            path
+        })
+        .collect();
+        let tex = "\\ifPDFTeX\\else\n  \\setmainfont[]{DejaVu Serif}\n  \\setsansfont{Helvetica}\n\\fi\n";
+
+        let bundle = write_bundle(work_dir.path(), &bundle_tex(tex, &fonts), &fonts).unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(bundle)).unwrap();
+        let mut document = String::new();
+        archive
+            .by_name(BUNDLE_TEX)
+            .unwrap()
+            .read_to_string(&mut document)
+            .unwrap();
+        assert_eq!(
+            document,
+            "\\ifPDFTeX\\else\n  \\setmainfont[Path=fonts/,BoldFont=DejaVuSerif-Bold.ttf,\
+             ItalicFont=DejaVuSerif-Italic.ttf,BoldItalicFont=DejaVuSerif-BoldItalic.ttf]\
+             {DejaVuSerif.ttf}\n  \\setsansfont{Helvetica}\n\\fi\n"
+        );
+        for name in [
+            "DejaVuSerif.ttf",
+            "DejaVuSerif-Bold.ttf",
+            "DejaVuSerif-Italic.ttf",
+            "DejaVuSerif-BoldItalic.ttf",
+        ] {
+            let mut content = String::new();
+            archive
+                .by_name(&format!("fonts/{}", name))
+                .unwrap()
+                .read_to_string(&mut content)
+                .unwrap();
+            assert_eq!(content, name);
+        }
+    }
+
+    #[test]
+    fn escape_latex_escapes_every_special_character() {
+        assert_eq!(
+            escape_latex(r"R&D 100% $5 #1 a_b {x} ~ ^ \ ok"),
+            r"R\&D 100\% \$5 \#1 a\_b \{x\} \textasciitilde{} \textasciicircum{} \textbackslash{} ok"
+        );
+    }
+
+    #[test]
+    fn page_markings_puts_the_banner_in_the_header_and_footer() {
+        let marking = Marking::new("secret//rel to usa_fvey");
+
+        let preamble = page_markings(Some(&marking), Some("Distribution D & E"));
+
+        assert!(preamble.contains(r"\definecolor{markingbg}{HTML}{C8102E}"));
+        assert!(preamble.contains(r"\definecolor{markingfg}{HTML}{FFFFFF}"));
+        assert!(preamble.contains(r"\strut SECRET//REL TO USA\_FVEY}"));
+        assert!(preamble.contains(r"\fancyhead[C]{\classificationbanner}"));
+        assert!(preamble.contains(
+            r"{\scriptsize Distribution D \& E\par}\vspace{2pt}\thepage\par\vspace{2pt}\classificationbanner"
+        ));
+    }
+
+    #[test]
+    fn page_markings_without_a_classification_keeps_only_the_footer() {
+        for statement in [None, Some("Distribution A")] {
+            let preamble = page_markings(None, statement);
+
+            assert!(!preamble.contains(r"\classificationbanner"));
+            assert!(!preamble.contains("markingbg"));
+            assert!(preamble.contains(r"\fancyhead[C]{}"));
+            assert!(preamble.contains(r"\thepage"));
+            assert_eq!(
+                preamble.contains(r"{\scriptsize Distribution A\par}"),
+                statement.is_some()
+            );
+        }
+    }
+
+    #[test]
+    fn prepare_markdown_sets_only_the_appendices_in_landscape() {
+        let markdown = "# Scope\n\n```\n# Appendix in a code block\n```\n\n## Appendix A: Traceability\n\n| a | b |\n\n# Appendix B\n";
+        let landscape = PdfLayout {
+            landscape_appendices: true,
+            ..PdfLayout::default()
+        };
+
+        assert_eq!(
+            prepare_markdown(markdown, &landscape),
+            "# Scope\n\n```\n# Appendix in a code block\n```\n\n\n\n\
+             ```{=latex}\n\\begin{landscape}\n```\n\n\
+             ## Appendix A: Traceability\n\n| a | b |\n\n# Appendix B\n\n\n\
+             ```{=latex}\n\\end{landscape}\n```\n"
+        );
+        assert!(matches!(
+            prepare_markdown("# Scope\n\n## Appendix A\n", &PdfLayout::default()),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            prepare_markdown("# Scope\n\n### Appendix C\n", &landscape),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn is_appendix_heading_matches_level_one_and_two_headings() {
+        for line in ["# Appendix A", "## appendix: Glossary\n", "##  APPENDIX B"] {
+            assert!(is_appendix_heading(line), "{:?}", line);
+        }
+        for line in [
+            "### Appendix C",
+            "#Appendix A",
+            "Appendix A",
+            "# Appendices and annexes are not special",
+            "# See the Appendix",
+        ] {
+            assert!(!is_appendix_heading(line), "{:?}", line);
+        }
+    }
 }
@@ -0,0 +1,285 @@
+// document-generation-service/src/renderers/process.rs
+
+use crate::error::{DocumentError, Result};
+use std::process::{Output, Stdio};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::process::{Child, Command};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+/// Run `cmd` to completion without blocking the runtime. When `timeout`
+/// elapses or `cancel` fires first, the process and everything it started
+/// (XeLaTeX under Pandoc) are killed and reaped, and a `PandocError` is
+/// returned.
+pub async fn run(
+    cmd: &mut Command,
+    timeout: Duration,
+    cancel: &CancellationToken,
+) -> Result<Output> {
+    let program = cmd.as_std().get_program().to_string_lossy().into_owned();
+    if cancel.is_cancelled() {
+        return Err(DocumentError::PandocError(format!(
+            "{} not started: processing was cancelled",
+            program
+        )));
+    }
+
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // Lead a new process group so the whole tree can be signalled at once
+    #[cfg(unix)]
+    cmd.process_group(0);
+
+    debug!("Running: {:?}", cmd);
+
+    let mut child = cmd.spawn()?;
+    let mut group = GroupGuard(child.id());
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+
+    let stopped = {
+        let completion = async {
+            let (mut out, mut err) = (Vec::new(), Vec::new());
+            let (status, _, _) = tokio::try_join!(
+                child.wait(),
+                stdout.read_to_end(&mut out),
+                stderr.read_to_end(&mut err)
+            )?;
+            Ok::<_, std::io::Error>(Output {
+                status,
+                stdout: out,
+                stderr: err,
+            })
+        };
+
+        tokio::select! {
+            output = completion => {
+                // Exited on its own; the group is left alone from here on
+                group.disarm();
+                return Ok(output?);
+            }
+            _ = tokio::time::sleep(timeout) => {
+                format!("{} timed out after {}s", program, timeout.as_secs())
+            }
+            _ = cancel.cancelled() => format!("{} cancelled", program),
+        }
+    };
+
+    warn!(program = %program, reason = %stopped, "Killing subprocess");
+    group.disarm();
+    kill_tree(&mut child).await;
+    Err(DocumentError::PandocError(stopped))
+}
+
+/// Run a render step: wait for one of `permits`, run `cmd` as `run` does,
+/// and return its stdout. An unsuccessful exit becomes a `PandocError`
+/// carrying the program's stderr, or its stdout when stderr is empty
+/// (XeLaTeX reports errors there).
+pub async fn render(
+    permits: &Semaphore,
+    cmd: &mut Command,
+    timeout: Duration,
+    cancel: &CancellationToken,
+) -> Result<Vec<u8>> {
+    let _permit = permits
+        .acquire()
+        .await
+        .map_err(|e| DocumentError::PandocError(e.to_string()))?;
+
+    let output = run(cmd, timeout, cancel).await?;
+
+    if !output.status.success() {
+        let report = if output.stderr.iter().all(u8::is_ascii_whitespace) {
+            &output.stdout
+        } else {
+            &output.stderr
+        };
+        return Err(DocumentError::PandocError(
+            String::from_utf8_lossy(report).into_owned(),
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Whether `pandoc` can be run here; renderer tests skip when it cannot.
+#[cfg(test)]
+pub(crate) fn pandoc_available() -> bool {
+    std::process::Command::new("pandoc")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Kills the process group of a running child when dropped, so a render
+/// whose future is dropped (on shutdown) takes XeLaTeX and anything else
+/// Pandoc started down with it; `kill_on_drop` only reaches the child.
+struct GroupGuard(Option<u32>);
+
+impl GroupGuard {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        if let Some(pid) = self.0.take() {
+            kill_group(pid);
+        }
+    }
+}
+
+/// SIGKILL every process in the group `pid` leads.
+fn kill_group(pid: u32) {
+    #[cfg(unix)]
+    // SAFETY: kill(2) with a negative pid signals the process group the
+    // child leads; it touches no memory.
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+/// Kill the child's process group, then the child itself, and wait for it so
+/// no zombie is left behind.
+async fn kill_tree(child: &mut Child) {
+    if let Some(pid) = child.id() {
+        kill_group(pid);
+    }
+    if let Err(e) = child.kill().await {
+        warn!("Failed to kill subprocess: {}", e);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::time::Instant;
+    use tempfile::NamedTempFile;
+
+    /// A shell that records its pid, which is also its process group, then
+    /// leaves a background `sleep` behind while waiting on another.
+    const SLEEPERS: &str = "echo $$ > \"$0\"; sleep 30 & sleep 30";
+
+    async fn render_sh(script: &str) -> Result<Vec<u8>> {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(script);
+        render(
+            &Semaphore::new(1),
+            &mut cmd,
+            Duration::from_secs(10),
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn render_returns_stdout_and_reports_failures() {
+        assert_eq!(render_sh("printf body").await.unwrap(), b"body");
+
+        let stderr = render_sh("echo out; echo broken >&2; exit 1").await;
+        assert!(matches!(stderr, Err(DocumentError::PandocError(m)) if m == "broken\n"));
+
+        let stdout = render_sh("echo '! Undefined control sequence.'; exit 1").await;
+        assert!(
+            matches!(stdout, Err(DocumentError::PandocError(m)) if m.contains("Undefined control"))
+        );
+    }
+
+    fn sh(script: &str, arg0: &Path) -> Command {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(script).arg(arg0);
+        cmd
+    }
+
+    /// Whether every process in group `pgid` is gone (or a zombie) within a
+    /// couple of seconds.
+    #[cfg(target_os = "linux")]
+    async fn group_exited(pid_file: &Path) -> bool {
+        let pgid = std::fs::read_to_string(pid_file)
+            .unwrap()
+            .trim()
+            .to_string();
+        assert!(!pgid.is_empty(), "shell never started");
+        for _ in 0..100 {
+            let live = std::fs::read_dir("/proc")
+                .unwrap()
+                .filter_map(|entry| std::fs::read_to_string(entry.ok()?.path().join("stat")).ok())
+                .filter(|stat| {
+                    // State, ppid and pgrp follow the parenthesised command
+                    let fields: Vec<&str> =
+                        stat[stat.rfind(')').unwrap() + 2..].split(' ').collect();
+                    fields[0] != "Z" && fields[2] == pgid
+                })
+                .count();
+            if live == 0 {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        false
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn run_kills_the_process_group_on_timeout() {
+        let pid_file = NamedTempFile::new().unwrap();
+        let mut cmd = sh(SLEEPERS, pid_file.path());
+
+        let started = Instant::now();
+        let result = run(
+            &mut cmd,
+            Duration::from_millis(100),
+            &CancellationToken::new(),
+        )
+        .await;
+
+        assert!(matches!(result, Err(DocumentError::PandocError(m)) if m.contains("timed out")));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(group_exited(pid_file.path()).await);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn run_kills_the_process_group_when_cancelled() {
+        let pid_file = NamedTempFile::new().unwrap();
+        let mut cmd = sh(SLEEPERS, pid_file.path());
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            trigger.cancel();
+        });
+
+        let started = Instant::now();
+        let result = run(&mut cmd, Duration::from_secs(30), &cancel).await;
+
+        assert!(matches!(result, Err(DocumentError::PandocError(m)) if m.contains("cancelled")));
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(group_exited(pid_file.path()).await);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn dropping_run_kills_the_process_group() {
+        let pid_file = NamedTempFile::new().unwrap();
+        let mut cmd = sh(SLEEPERS, pid_file.path());
+        let cancel = CancellationToken::new();
+
+        let abandoned = tokio::time::timeout(
+            Duration::from_millis(100),
+            run(&mut cmd, Duration::from_secs(30), &cancel),
+        )
+        .await;
+
+        assert!(abandoned.is_err());
+        assert!(group_exited(pid_file.path()).await);
+    }
+}